@@ -0,0 +1,57 @@
+use serde::{Serialize, Deserialize};
+
+use crate::add_base64;
+
+/// Names an account's cookie jar in the keyring. The account from before multi-account support keeps `"default"`.
+#[derive(Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct AccountID(pub String);
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccountSummary {
+    pub id: AccountID,
+    /// Empty until the account has been signed into.
+    pub username: String,
+    #[serde(default)]
+    pub remember_password: bool,
+}
+
+impl AccountSummary {
+    pub fn label(&self) -> &str {
+        match self.username.as_str() {
+            "" => "New account",
+            username => username,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct AccountList {
+    pub accounts: Vec<AccountSummary>,
+    pub active: Option<AccountID>,
+}
+
+add_base64! { AccountList }
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ChildID(pub u64);
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Child {
+    pub id: ChildID,
+    pub name: String,
+}
+
+/// The children linked to a parent account. Student accounts have none.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ParentInfo {
+    pub children: Vec<Child>,
+    pub active: Option<ChildID>,
+}
+
+impl ParentInfo {
+    pub fn is_parent(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+add_base64! { ParentInfo }
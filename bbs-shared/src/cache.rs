@@ -1,5 +1,5 @@
-use std::{collections::HashMap, sync::{Arc, Mutex}, time::{UNIX_EPOCH, SystemTime, Duration}};
-use crate::{data::{ClassPageData, ClassEntry, ClassItemEntryContents}, ClassID, MaterialID};
+use std::{collections::{HashMap, HashSet}, sync::{Arc, Mutex}, time::{UNIX_EPOCH, SystemTime, Duration}};
+use crate::{data::{ClassPageData, ClassEntry, Instructor, MemberPage, ClassItemEntryContents, FeedItem}, grades::GradeReport, ClassID, MaterialID, SectionID};
 
 pub type AMutComponent<T> = Arc<Mutex<T>>;
 
@@ -11,6 +11,28 @@ pub struct TimedComponent<T> {
 
 const TIMEOUT: Duration = Duration::from_secs(60 * 10);
 
+impl<T> TimedComponent<T> {
+    pub fn is_fresh(&self) -> bool {
+        SystemTime::now()
+            .duration_since(
+                self
+                    .prev_update
+                    .lock()
+                    .ok()
+                    .map(|guard| *guard)
+                    .unwrap_or(UNIX_EPOCH)
+            )
+            .unwrap_or(BackendCache::get_timeout()) < BackendCache::get_timeout()
+    }
+
+    pub fn touch(&self) {
+        match self.prev_update.lock() {
+            Ok(mut prev_update) => *prev_update = SystemTime::now(),
+            Err(e) => eprintln!("Cache lock poisoned: {:#?}", e),
+        }
+    }
+}
+
 impl<T> Default for TimedComponent<T>
 where T: Default {
     fn default() -> TimedComponent<T> {
@@ -26,6 +48,14 @@ pub struct BackendCache {
     pub class_listing: TimedComponent<AMutComponent<Option<Vec<ClassEntry>>>>,
     pub class_data: AMutComponent<HashMap<ClassID, TimedComponent<ClassPageData>>>,
     pub assignment_data: TimedComponent<Option<HashMap<MaterialID, ClassItemEntryContents>>>,
+    pub home_feed: TimedComponent<AMutComponent<Option<Vec<FeedItem>>>>,
+    pub grades: TimedComponent<AMutComponent<Option<Vec<GradeReport>>>>,
+    pub thumbnail_urls: AMutComponent<HashMap<SectionID, String>>,
+    pub instructors: AMutComponent<HashMap<SectionID, Vec<Instructor>>>,
+    /// Sections whose instructors are being fetched, so repeated requests don't start another fetch.
+    pub instructors_pending: AMutComponent<HashSet<SectionID>>,
+    /// Only the unfiltered list; searches always go to schoology.
+    pub member_pages: AMutComponent<HashMap<(SectionID, u32), TimedComponent<MemberPage>>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -92,6 +122,30 @@ impl BackendCache {
         }
     }
 
+    pub fn get_home_feed_state(&self) -> CacheDataState {
+        if self.home_feed.data.lock().unwrap().is_some() {
+            if self.home_feed.is_fresh() {
+                CacheDataState::Ok
+            } else {
+                CacheDataState::Stale
+            }
+        } else {
+            CacheDataState::None
+        }
+    }
+
+    pub fn get_grades_state(&self) -> CacheDataState {
+        if self.grades.data.lock().unwrap().is_some() {
+            if self.grades.is_fresh() {
+                CacheDataState::Ok
+            } else {
+                CacheDataState::Stale
+            }
+        } else {
+            CacheDataState::None
+        }
+    }
+
     // fn get_class_listing_state(&self) -> CacheDataState {
     //     if self
     //         .class_listing
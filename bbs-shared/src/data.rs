@@ -1,10 +1,10 @@
-use std::{collections::HashMap, rc::Rc, cell::RefCell, ops::{DerefMut, Deref}, num::ParseIntError};
+use std::{collections::{BTreeSet, HashMap, HashSet}, rc::Rc, cell::RefCell, ops::{DerefMut, Deref}, fmt::Display};
 use std::cmp::Ordering::*;
 
 use serde::{Serialize, Deserialize};
 use yew::Properties;
 
-use crate::{ClassID, MaterialID, DueDate, add_base64, SectionID};
+use crate::{accounts::{AccountList, ParentInfo}, ClassID, MaterialID, DueDate, add_base64, SectionID, grades::GradeReport, settings::Settings, events::UploadProgress, messages::{MessageThread, ThreadSummary}, notifications::Notification, schedule::LetterDay, section::{format_section, parse_section, Period}};
 
 pub type OptMutComponent<T> = Rc<RefCell<Option<T>>>;
 
@@ -44,13 +44,59 @@ impl<T> Deref for Keyed<T> {
 pub struct FrontendData {
     pub classes: Keyed<OptMutComponent<Vec<ClassEntry>>>,
     pub curr_class_data: Keyed<OptMutComponent<Vec<Assignment>>>,
+    pub home_feed: Keyed<OptMutComponent<Vec<FeedItem>>>,
+    pub grades: Keyed<OptMutComponent<Vec<GradeReport>>>,
+    pub settings: Keyed<OptMutComponent<Settings>>,
+    pub upload_progress: Keyed<OptMutComponent<UploadProgress>>,
+    pub active_upload: Keyed<OptMutComponent<u64>>,
+    pub ready_assets: Keyed<OptMutComponent<HashSet<String>>>,
+    pub rosters: Keyed<OptMutComponent<HashMap<SectionID, ClassRoster>>>,
+    pub inbox: Keyed<OptMutComponent<Vec<ThreadSummary>>>,
+    pub message_thread: Keyed<OptMutComponent<MessageThread>>,
+    pub notifications: Keyed<OptMutComponent<Vec<Notification>>>,
+    pub upcoming: Keyed<OptMutComponent<Vec<UpcomingItem>>>,
+    pub accounts: Keyed<OptMutComponent<AccountList>>,
+    pub children: Keyed<OptMutComponent<ParentInfo>>,
 }
 
 impl FrontendData {
+    pub fn grade_report(&self, section: SectionID) -> Option<GradeReport> {
+        self.grades
+            .borrow()
+            .as_ref()?
+            .iter()
+            .find(|report| report.section == section)
+            .cloned()
+    }
+
+    /// When the section's soonest upcoming item is due, if it has any.
+    pub fn next_due(&self, section: SectionID) -> Option<i64> {
+        self.upcoming
+            .borrow()
+            .as_ref()?
+            .iter()
+            .filter(|item| item.section == section)
+            .map(|item| item.due)
+            .min()
+    }
+
     pub fn empty() -> Self {
         Self {
             classes: Keyed(Rc::new(RefCell::new(None)), 0),
             curr_class_data: Keyed(Rc::new(RefCell::new(None)), 0),
+            home_feed: Keyed(Rc::new(RefCell::new(None)), 0),
+            grades: Keyed(Rc::new(RefCell::new(None)), 0),
+            settings: Keyed(Rc::new(RefCell::new(None)), 0),
+            upload_progress: Keyed(Rc::new(RefCell::new(None)), 0),
+            active_upload: Keyed(Rc::new(RefCell::new(None)), 0),
+            ready_assets: Keyed(Rc::new(RefCell::new(None)), 0),
+            rosters: Keyed(Rc::new(RefCell::new(None)), 0),
+            inbox: Keyed(Rc::new(RefCell::new(None)), 0),
+            message_thread: Keyed(Rc::new(RefCell::new(None)), 0),
+            notifications: Keyed(Rc::new(RefCell::new(None)), 0),
+            upcoming: Keyed(Rc::new(RefCell::new(None)), 0),
+            accounts: Keyed(Rc::new(RefCell::new(None)), 0),
+            children: Keyed(Rc::new(RefCell::new(None)), 0),
         }
     }
 }
@@ -59,43 +105,112 @@ impl FrontendData {
 pub struct ClassEntry {
     pub name: String,
     pub section: SectionData,
-    pub picture: Vec<u8>,
+    /// Asset url served by the backend's `bbs` protocol, if the class has a thumbnail.
+    pub picture: Option<String>,
     pub id: ClassID,
     pub section_nid: SectionID,
-    pub instructors: Option<Vec<String>>,
+    pub instructors: Option<Vec<Instructor>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instructor {
+    pub name: String,
+    pub email: Option<String>,
+    pub profile_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MemberRole {
+    Admin,
+    Member,
+}
+
+impl MemberRole {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MemberRole::Admin => "Teacher",
+            MemberRole::Member => "Student",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    pub role: MemberRole,
+    /// Asset url proxied through the backend, since avatars need the session cookies.
+    pub avatar: Option<String>,
+    pub profile_url: Option<String>,
+}
+
+/// One page of a section's member list, as schoology paginates it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct MemberPage {
+    pub members: Vec<Member>,
+    pub has_next: bool,
+}
+
+add_base64! { MemberPage }
+
+/// Every member page loaded so far for a section's current search.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ClassRoster {
+    pub members: Vec<Member>,
+    /// Schoology does the searching, so the pages only hold members matching this.
+    pub query: String,
+    /// Pages land in order, so anything but the next one is a repeat.
+    pub pages_loaded: u32,
+    pub next_page: Option<u32>,
+    /// The page being fetched, if any.
+    pub pending: Option<u32>,
+}
+
+impl ClassEntry {
+    /// Classes prefixed with `~` are ungraded by convention.
+    pub fn affects_gpa(&self) -> bool {
+        !self.name.starts_with('~')
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name.strip_prefix('~').unwrap_or(&self.name)
+    }
 }
 
 impl PartialOrd for ClassEntry {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(match (&self.section.guts, &other.section.guts) {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders by period, then by the earliest day met, then lab sections after the lecture, then falls back on name
+/// and id. The remaining fields break any tie after that, so only equal entries compare equal.
+impl Ord for ClassEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let section_order = match (&self.section.guts, &other.section.guts) {
             (SectionDataGuts::Good {
                 period: self_period,
                 days: self_days,
+                lab: self_lab,
             }, SectionDataGuts::Good {
                 period: other_period,
                 days: other_days,
-            }) => match self_period.cmp(other_period) {
-                Equal => self_days
-                    .iter()
-                    .zip(other_days)
-                    .filter_map(|(s, o)| match o.cmp(s) {
-                        Equal => None,
-                        order => Some(order)
-                    })
-                    .next()
-                    .unwrap_or(Equal),
-                order => order,
-            },
+                lab: other_lab,
+            }) => self_period
+                .cmp(other_period)
+                .then_with(|| self_days.cmp(other_days))
+                .then_with(|| self_lab.cmp(other_lab)),
             (SectionDataGuts::Good {..}, &SectionDataGuts::Bad(_)) => Less,
             (SectionDataGuts::Bad(_), &SectionDataGuts::Good {..}) => Greater,
-            _ => Equal
-        })
-    }
-}
-
-impl Ord for ClassEntry {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+            (SectionDataGuts::Bad(self_title), SectionDataGuts::Bad(other_title)) => self_title.cmp(other_title),
+        };
+
+        section_order
+            .then_with(|| self.display_name().cmp(other.display_name()))
+            .then_with(|| self.name.cmp(&other.name))
+            .then_with(|| self.id.0.cmp(&other.id.0))
+            .then_with(|| self.section_nid.0.cmp(&other.section_nid.0))
+            .then_with(|| self.picture.cmp(&other.picture))
+            .then_with(|| self.instructors.cmp(&other.instructors))
     }
 }
 
@@ -108,8 +223,7 @@ pub struct SectionData {
 
 impl From<&str> for SectionData {
     fn from(s: &str) -> Self {
-        
-        match SectionDataGuts::try_good_from_str(s) {
+        match parse_section(s) {
             Ok(guts) => Self { guts },
             Err(e) => {
                 eprintln!("{}", e);
@@ -136,46 +250,27 @@ impl DerefMut for SectionData {
 pub enum SectionDataGuts {
     Bad(String),
     Good {
-        days: [bool; 7],
-        period: u64,
+        days: BTreeSet<LetterDay>,
+        period: Period,
+        lab: bool,
+    }
+}
+
+impl Display for SectionDataGuts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bad(s) => f.write_str(s),
+            Self::Good { days, period, lab } => f.write_str(&format_section(period, *lab, days)),
+        }
     }
 }
 
 impl SectionDataGuts {
-    fn try_good_from_str(s: &str) -> Result<Self, String> {
-        let paren_location = s.find('(').ok_or("Failed to find opening parenthesis!")?;
-        
-        let period_slice = &s[0..paren_location];
-
-        let period = period_slice.parse().map_err(|err: ParseIntError| err.to_string())?;
-        
-        let days_slice = &s[paren_location+1..s.len()-1];
-
-        let days = (| | {
-            const DICT: &str = "ABCDE";
-
-            let mut days = [false; 7];
-            
-            days_slice
-                .split(',')
-                .filter_map(|day_days| {
-                    day_days
-                        .split_once('-')
-                        .map_or(
-                            Some((|n| n..n+1)(DICT.find(day_days.chars().nth(0)?)?)),
-                            |(start, end)| Some(DICT.find(start.chars().nth(0)?)?..DICT.find(end.chars().nth(0)?)? + 1),
-                        )
-                })
-                .flatten()
-                .for_each(|day_num| days[day_num + 1] = true);
-
-            Some(days)
-        })().unwrap_or([false; 7]);
-
-        Ok(Self::Good {
-            period,
-            days,
-        })
+    pub fn meets_on(&self, letter: LetterDay) -> bool {
+        match self {
+            Self::Good { days, .. } => days.contains(&letter),
+            Self::Bad(_) => false,
+        }
     }
 }
 
@@ -225,11 +320,107 @@ pub enum AssignmentType {
     File,
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SubmissionState {
+    NotSubmitted,
+    Submitted,
+    Late,
+    Graded,
+    Excused,
+}
+
+impl SubmissionState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SubmissionState::NotSubmitted => "Not submitted",
+            SubmissionState::Submitted => "Submitted",
+            SubmissionState::Late => "Late",
+            SubmissionState::Graded => "Graded",
+            SubmissionState::Excused => "Excused",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SubmittedFile {
+    pub name: String,
+    pub url: String,
+    pub preview: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Submission {
+    pub state: SubmissionState,
+    pub submitted_at: Option<String>,
+    pub files: Vec<SubmittedFile>,
+}
+
+add_base64! { Submission }
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Assignment {
     pub id : MaterialID,
     pub kind : AssignmentType, // what type of thing it is... assignment, discussion, folder, etc.
     pub title : String,
     pub body : String,
-    pub duedate : String
-}
\ No newline at end of file
+    /// Images from the body, as asset urls. The body itself is only kept as text.
+    pub images: Vec<String>,
+    pub duedate : String,
+    pub submission: Option<Submission>, // only scraped for actual assignments
+}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FeedItem {
+    Update {
+        author: String,
+        section: Option<SectionID>,
+        body: String,
+        posted: String,
+    },
+    NewMaterial {
+        section: SectionID,
+        material: MaterialID,
+        kind: AssignmentType,
+        title: String,
+        posted: String,
+    },
+    Graded {
+        section: SectionID,
+        material: Option<MaterialID>,
+        title: String,
+        posted: String,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpcomingItem {
+    pub section: SectionID,
+    pub material: Option<MaterialID>,
+    pub title: String,
+    /// Unix timestamp, in seconds.
+    pub due: i64,
+}
+
+impl FeedItem {
+    pub fn section(&self) -> Option<SectionID> {
+        match self {
+            FeedItem::Update { section, .. } => *section,
+            FeedItem::NewMaterial { section, .. } | FeedItem::Graded { section, .. } => Some(*section),
+        }
+    }
+
+    pub fn material(&self) -> Option<MaterialID> {
+        match self {
+            FeedItem::Update { .. } => None,
+            FeedItem::NewMaterial { material, .. } => Some(*material),
+            FeedItem::Graded { material, .. } => *material,
+        }
+    }
+
+    pub fn posted(&self) -> &str {
+        match self {
+            FeedItem::Update { posted, .. }
+            | FeedItem::NewMaterial { posted, .. }
+            | FeedItem::Graded { posted, .. } => posted,
+        }
+    }
+}
@@ -1,7 +1,15 @@
 use bincode::ErrorKind;
-use serde::{Serialize, Deserialize};
+use serde::{de::DeserializeOwned, Serialize, Deserialize};
 use std::{error::Error, fmt::Display};
 
+/// Undoes the `Into<String>` encoding. A string that isn't base64 is reported like any other bad input
+/// instead of panicking, since it can come from anywhere that rejects a promise.
+fn decode_error<T: DeserializeOwned>(string: String) -> Result<T, Box<ErrorKind>> {
+    let bytes = base64::decode(string).map_err(|e| Box::new(ErrorKind::Custom(e.to_string())))?;
+    bincode::deserialize(&bytes)
+}
+
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct CredSetError;
 
@@ -20,15 +28,207 @@ impl Display for CredSetError {
 impl Error for CredSetError {}
 
 
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum SettingsError {
+    DecodeError,
+    LockError,
+    WriteError,
+}
+
+impl Into<String> for SettingsError {
+    fn into(self) -> String {
+        base64::encode(bincode::serialize(&self).unwrap())
+    }
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SettingsError::DecodeError => "Failed to decode settings!",
+            SettingsError::LockError => "Failed to get lock on settings!",
+            SettingsError::WriteError => "Failed to write settings to disk!",
+        })
+    }
+}
+
+impl Error for SettingsError {}
+
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LoginError {
+pub enum UploadError {
+    ReadFileError,
+    FindFormError,
+    RequestError,
+    RejectedError,
     SerializationError,
+}
+
+impl Into<String> for UploadError {
+    fn into(self) -> String {
+        base64::encode(bincode::serialize(&self).unwrap())
+    }
+}
+impl TryFrom<String> for UploadError {
+    type Error = Box<ErrorKind>;
+    fn try_from(string: String) -> Result<Self, Box<ErrorKind>> {
+        decode_error(string)
+    }
+}
+
+impl Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            UploadError::ReadFileError => "Failed to read the selected file!",
+            UploadError::FindFormError => "Could not find the submission form for this assignment!",
+            UploadError::RequestError => "Failed to reach Schoology!",
+            UploadError::RejectedError => "Schoology rejected the submission!",
+            UploadError::SerializationError => "Internal error encountered!",
+        })
+    }
+}
+
+impl Error for UploadError {}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageError {
     FindFormError,
-    InvalidCredsError,
     RequestError,
-    LaterRequestError,
+    RejectedError,
     DecodeError,
+    SerializationError,
+}
+
+impl Into<String> for MessageError {
+    fn into(self) -> String {
+        base64::encode(bincode::serialize(&self).unwrap())
+    }
+}
+impl TryFrom<String> for MessageError {
+    type Error = Box<ErrorKind>;
+    fn try_from(string: String) -> Result<Self, Box<ErrorKind>> {
+        decode_error(string)
+    }
+}
+
+impl Display for MessageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MessageError::FindFormError => "Could not find the message form!",
+            MessageError::RequestError => "Failed to reach Schoology!",
+            MessageError::RejectedError => "Schoology rejected the message!",
+            MessageError::DecodeError => "Failed to read the message!",
+            MessageError::SerializationError => "Internal error encountered!",
+        })
+    }
+}
+
+impl Error for MessageError {}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountError {
+    UnknownAccount,
+    LockError,
+    WriteError,
+    SessionStoreError,
+    RequestError,
+    SerializationError,
+    /// There's no password in memory to remember; it has to be typed in again.
+    NoCredentials,
+}
+
+impl Into<String> for AccountError {
+    fn into(self) -> String {
+        base64::encode(bincode::serialize(&self).unwrap())
+    }
+}
+impl TryFrom<String> for AccountError {
+    type Error = Box<ErrorKind>;
+    fn try_from(string: String) -> Result<Self, Box<ErrorKind>> {
+        decode_error(string)
+    }
+}
+
+impl Display for AccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AccountError::UnknownAccount => "That account no longer exists!",
+            AccountError::LockError => "Failed to get lock on accounts!",
+            AccountError::WriteError => "Failed to write accounts to disk!",
+            AccountError::SessionStoreError => "Failed to update the saved session!",
+            AccountError::RequestError => "Failed to reach Schoology!",
+            AccountError::SerializationError => "Internal error encountered!",
+            AccountError::NoCredentials => "Sign in again with \"Remember password\" ticked to save your password.",
+        })
+    }
+}
+
+impl Error for AccountError {}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SectionParseError {
+    Empty,
+    MissingPeriod,
+    InvalidPeriod(String),
+    BackwardsPeriods(u64, u64),
+    MissingOpenParen,
+    MissingCloseParen,
+    NoDays,
+    InvalidDay(char),
+    BackwardsDays(char, char),
+    UnexpectedText(String),
+}
+
+impl Display for SectionParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionParseError::Empty => f.write_str("Section title is empty!"),
+            SectionParseError::MissingPeriod => f.write_str("Failed to find a period!"),
+            SectionParseError::InvalidPeriod(period) => write!(f, "\"{}\" is not a valid period number!", period),
+            SectionParseError::BackwardsPeriods(first, last) => write!(f, "Period range {}-{} runs backwards!", first, last),
+            SectionParseError::MissingOpenParen => f.write_str("Failed to find opening parenthesis!"),
+            SectionParseError::MissingCloseParen => f.write_str("Failed to find closing parenthesis!"),
+            SectionParseError::NoDays => f.write_str("Section meets on no days!"),
+            SectionParseError::InvalidDay(day) => write!(f, "'{}' is not a letter day!", day),
+            SectionParseError::BackwardsDays(start, end) => write!(f, "Day range {}-{} runs backwards!", start, end),
+            SectionParseError::UnexpectedText(text) => write!(f, "Unexpected \"{}\" in section title!", text),
+        }
+    }
+}
+
+impl Error for SectionParseError {}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoginError {
+    SerializationError,
+    /// Schoology never answered, with the underlying reason.
+    NetworkUnreachable(String),
+    /// Schoology answered with its maintenance or error page instead of the site.
+    Maintenance { status: u16 },
+    InvalidCredentials { username: String },
+    /// Too many failed attempts, or the school disabled the account. Holds Schoology's own explanation.
+    AccountLocked(String),
+    /// Holds the page that asked for it.
+    CaptchaRequired { url: String },
+    /// Neither a login form nor a signed in page, so something changed on Schoology's end.
+    UnexpectedPage { url: String, status: u16 },
+    /// These three hold the underlying reason.
+    RequestError(String),
+    LaterRequestError(String),
+    DecodeError(String),
     JsonError,
+    /// The school signs in through an identity provider, so the username and password form can't be used.
+    SsoRequired,
+    /// Holds what Schoology did instead of starting the session.
+    SsoFailed(String),
+    /// Not really a failure: the password was fine and Schoology wants a verification code next.
+    CodeRequired { hint: String },
+    InvalidCode { hint: String },
+    /// There's no challenge waiting for a code, usually because the app restarted in between.
+    CodeExpired,
 }
 
 impl Into<String> for LoginError {
@@ -39,10 +239,37 @@ impl Into<String> for LoginError {
 impl TryFrom<String> for LoginError {
     type Error = Box<ErrorKind>;
     fn try_from(string: String) -> Result<Self, Box<ErrorKind>> {
-        bincode::deserialize(&base64::decode(string).unwrap())
+        decode_error(string)
     }
 }
 
+impl Display for LoginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginError::SerializationError => f.write_str("Internal error encountered!"),
+            LoginError::NetworkUnreachable(reason) => write!(f, "Failed to reach Schoology: {}", reason),
+            LoginError::Maintenance { status } => write!(f, "Schoology is down for maintenance (status {})!", status),
+            LoginError::InvalidCredentials { username } => write!(f, "Invalid password for {}!", username),
+            LoginError::AccountLocked(message) if message.is_empty() => f.write_str("This account is locked!"),
+            LoginError::AccountLocked(message) => write!(f, "This account is locked: {}", message),
+            LoginError::CaptchaRequired { url } => write!(f, "Schoology wants a CAPTCHA solved at {} before signing in!", url),
+            LoginError::UnexpectedPage { url, status } => write!(f, "Unexpected page from Schoology at {} (status {})!", url, status),
+            LoginError::RequestError(reason) => write!(f, "Failed to reach Schoology: {}", reason),
+            LoginError::LaterRequestError(reason) => write!(f, "Transiently failed to fulfill request: {}", reason),
+            LoginError::DecodeError(reason) => write!(f, "Invalid response text: {}", reason),
+            LoginError::JsonError => f.write_str("Invalid JSON detected!"),
+            LoginError::SsoRequired => f.write_str("This school signs in through SSO!"),
+            LoginError::SsoFailed(reason) => write!(f, "Single sign-on didn't finish: {}", reason),
+            LoginError::CodeRequired { hint } if hint.is_empty() => f.write_str("Schoology needs a verification code!"),
+            LoginError::CodeRequired { hint } => write!(f, "Schoology needs a verification code: {}", hint),
+            LoginError::InvalidCode { .. } => f.write_str("That verification code wasn't accepted!"),
+            LoginError::CodeExpired => f.write_str("The verification code request expired!"),
+        }
+    }
+}
+
+impl Error for LoginError {}
+
 // TODO: Implement later!
 // impl Display for CredSetError {
 //     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -1,20 +1,45 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{add_base64, errors::LoginError, MaterialID};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum Event {
     Navigation {
         target_type: TargetResourceType,
         id: Option<u64>,
     },
     NewDataReady { data_type: DataType },
+    UploadProgress(UploadProgress),
+    /// Sent once the SSO window has handed its session over, or failed to.
+    SsoFinished(Option<LoginError>),
+    /// An asset url that 404'd while it downloaded is now cached and worth asking for again.
+    AssetReady(String),
 }
 
+add_base64! { Event }
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum TargetResourceType {
     Main,
     Class,
     ClassMaterial,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum DataType {
     ClassListing,
     SingleClassListingTeachers { id: u64 },
     ClassData { id: u64 },
     MaterialData { id: u64 },
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UploadProgress {
+    /// Picked by the frontend for each submission, so progress that arrives after it finished can be dropped.
+    pub upload: u64,
+    pub material: MaterialID,
+    pub file_index: usize,
+    pub file_count: usize,
+    pub sent: u64,
+    pub total: u64,
+}
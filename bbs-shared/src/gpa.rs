@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{data::ClassEntry, grades::{Grade, GradeReport}, SectionID};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LetterCutoff {
+    pub letter: String,
+    pub min_percent: f64,
+    pub points: f64,
+}
+
+impl LetterCutoff {
+    fn new(letter: &str, min_percent: f64, points: f64) -> Self {
+        Self {
+            letter: letter.to_owned(),
+            min_percent,
+            points,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum CourseLevel {
+    Regular,
+    Honors,
+    AdvancedPlacement,
+}
+
+impl Default for CourseLevel {
+    fn default() -> Self {
+        CourseLevel::Regular
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum GpaScaleKind {
+    Unweighted,
+    Weighted {
+        honors_bump: f64,
+        ap_bump: f64,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GpaScale {
+    pub kind: GpaScaleKind,
+    /// Ordered from highest to lowest `min_percent`.
+    pub cutoffs: Vec<LetterCutoff>,
+}
+
+impl GpaScale {
+    fn standard_cutoffs() -> Vec<LetterCutoff> {
+        vec![
+            LetterCutoff::new("A+", 97.0, 4.0),
+            LetterCutoff::new("A", 93.0, 4.0),
+            LetterCutoff::new("A-", 90.0, 3.7),
+            LetterCutoff::new("B+", 87.0, 3.3),
+            LetterCutoff::new("B", 83.0, 3.0),
+            LetterCutoff::new("B-", 80.0, 2.7),
+            LetterCutoff::new("C+", 77.0, 2.3),
+            LetterCutoff::new("C", 73.0, 2.0),
+            LetterCutoff::new("C-", 70.0, 1.7),
+            LetterCutoff::new("D+", 67.0, 1.3),
+            LetterCutoff::new("D", 65.0, 1.0),
+            LetterCutoff::new("F", 0.0, 0.0),
+        ]
+    }
+
+    pub fn unweighted_4() -> Self {
+        Self {
+            kind: GpaScaleKind::Unweighted,
+            cutoffs: Self::standard_cutoffs(),
+        }
+    }
+
+    pub fn weighted_4() -> Self {
+        Self {
+            kind: GpaScaleKind::Weighted {
+                honors_bump: 0.5,
+                ap_bump: 1.0,
+            },
+            cutoffs: Self::standard_cutoffs(),
+        }
+    }
+
+    /// Puts the cutoffs back in the order `cutoff_for` relies on after one of them was edited.
+    pub fn sort_cutoffs(&mut self) {
+        self.cutoffs.sort_by(|a, b| b.min_percent.partial_cmp(&a.min_percent).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    /// Prefers the letter Schoology reports, falling back to the percentage when the letter is unknown.
+    pub fn cutoff_for(&self, grade: &Grade) -> Option<&LetterCutoff> {
+        grade.letter
+            .as_ref()
+            .and_then(|letter| self.cutoffs.iter().find(|cutoff| &cutoff.letter == letter))
+            .or_else(|| {
+                let percent = grade.percent?;
+                self.cutoffs.iter().find(|cutoff| percent >= cutoff.min_percent)
+            })
+    }
+
+    pub fn points_for(&self, grade: &Grade, level: CourseLevel) -> Option<f64> {
+        let points = self.cutoff_for(grade)?.points;
+
+        let bump = match (&self.kind, level) {
+            (GpaScaleKind::Weighted { .. }, _) if points <= 0.0 => 0.0,
+            (GpaScaleKind::Weighted { honors_bump, .. }, CourseLevel::Honors) => *honors_bump,
+            (GpaScaleKind::Weighted { ap_bump, .. }, CourseLevel::AdvancedPlacement) => *ap_bump,
+            _ => 0.0,
+        };
+
+        Some(points + bump)
+    }
+}
+
+impl Default for GpaScale {
+    fn default() -> Self {
+        Self::unweighted_4()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClassGpaSettings {
+    pub credits: f64,
+    pub level: CourseLevel,
+    /// Overrides the `~` prefix convention when set.
+    pub affects_gpa: Option<bool>,
+}
+
+impl Default for ClassGpaSettings {
+    fn default() -> Self {
+        Self {
+            credits: 1.0,
+            level: CourseLevel::Regular,
+            affects_gpa: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct GpaConfig {
+    pub scale: GpaScale,
+    pub classes: HashMap<SectionID, ClassGpaSettings>,
+}
+
+impl GpaConfig {
+    pub fn class_settings(&self, section: SectionID) -> ClassGpaSettings {
+        self.classes.get(&section).cloned().unwrap_or_default()
+    }
+
+    pub fn affects_gpa(&self, entry: &ClassEntry) -> bool {
+        self.class_settings(entry.section_nid)
+            .affects_gpa
+            .unwrap_or_else(|| entry.affects_gpa())
+    }
+}
+
+/// Credit-weighted GPA over every class that counts and has a current grade.
+pub fn compute_gpa(classes: &[ClassEntry], grades: &[GradeReport], config: &GpaConfig) -> Option<f64> {
+    let (total_points, total_credits) = classes
+        .iter()
+        .filter(|entry| config.affects_gpa(entry))
+        .filter_map(|entry| {
+            let settings = config.class_settings(entry.section_nid);
+            let grade = grades
+                .iter()
+                .find(|report| report.section == entry.section_nid)?
+                .current_grade()?;
+
+            Some((config.scale.points_for(grade, settings.level)? * settings.credits, settings.credits))
+        })
+        .fold((0.0, 0.0), |(points, credits), (class_points, class_credits)| (points + class_points, credits + class_credits));
+
+    if total_credits > 0.0 {
+        Some(total_points / total_credits)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+
+use crate::{MaterialID, SectionID};
+
+lazy_static::lazy_static! {
+    static ref PERCENT: Regex = Regex::new(r"(-?\d+(?:\.\d+)?)\s*%").unwrap();
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Grade {
+    pub letter: Option<String>,
+    pub percent: Option<f64>,
+}
+
+impl From<&str> for Grade {
+    fn from(s: &str) -> Self {
+        let percent = PERCENT
+            .captures(s)
+            .and_then(|captures| captures[1].parse().ok());
+
+        let letter = s
+            .split(|c| c == '(' || c == '%')
+            .next()
+            .map(str::trim)
+            .filter(|letter| !letter.is_empty() && letter.parse::<f64>().is_err())
+            .map(str::to_owned);
+
+        Self { letter, percent }
+    }
+}
+
+impl Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.letter, self.percent) {
+            (Some(letter), Some(percent)) => write!(f, "{} ({:.1}%)", letter, percent),
+            (Some(letter), None) => f.write_str(letter),
+            (None, Some(percent)) => write!(f, "{:.1}%", percent),
+            (None, None) => f.write_str("—"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GradedItem {
+    pub material: Option<MaterialID>,
+    pub title: String,
+    pub score: Option<f64>,
+    pub max: Option<f64>,
+    pub comment: Option<String>,
+}
+
+impl GradedItem {
+    pub fn percent(&self) -> Option<f64> {
+        match (self.score, self.max) {
+            (Some(score), Some(max)) if max > 0.0 => Some(score / max * 100.0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GradeCategory {
+    pub name: String,
+    /// Weight of the category as a percentage of the period grade, if the class is weighted.
+    pub weight: Option<f64>,
+    pub grade: Option<Grade>,
+    pub items: Vec<GradedItem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GradingPeriod {
+    pub name: String,
+    pub grade: Option<Grade>,
+    pub categories: Vec<GradeCategory>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GradeReport {
+    pub section: SectionID,
+    pub periods: Vec<GradingPeriod>,
+}
+
+impl GradeReport {
+    /// The latest grading period that has a grade posted, falling back to the last one listed.
+    pub fn current_period(&self) -> Option<&GradingPeriod> {
+        self.periods
+            .iter()
+            .rev()
+            .find(|period| period.grade.is_some())
+            .or_else(|| self.periods.last())
+    }
+
+    pub fn current_grade(&self) -> Option<&Grade> {
+        self.current_period()?.grade.as_ref()
+    }
+}
@@ -1,10 +1,19 @@
 pub mod reducer_actions;
 pub mod state;
 
+pub mod accounts;
 pub mod data;
 pub mod errors;
 pub mod events;
 pub mod cache;
+pub mod grades;
+pub mod gpa;
+pub mod what_if;
+pub mod settings;
+pub mod messages;
+pub mod notifications;
+pub mod schedule;
+pub mod section;
 
 use serde::{Serialize, Deserialize};
 
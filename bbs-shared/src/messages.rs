@@ -0,0 +1,63 @@
+use serde::{Serialize, Deserialize};
+
+use crate::add_base64;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ThreadID(pub u64);
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct UserID(pub u64);
+
+impl UserID {
+    /// Pulls the id out of a profile link such as `https://bca.schoology.com/user/1234/info`.
+    pub fn from_profile_url(url: &str) -> Option<Self> {
+        let (_, rest) = url.split_once("/user/")?;
+        rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+            .map(UserID)
+    }
+}
+
+/// A row of the inbox listing.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThreadSummary {
+    pub id: ThreadID,
+    pub subject: String,
+    pub participants: Vec<String>,
+    pub snippet: String,
+    pub updated: String,
+    pub unread: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Message {
+    pub author: String,
+    pub body: String,
+    pub sent: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MessageThread {
+    pub id: ThreadID,
+    pub subject: String,
+    pub participants: Vec<String>,
+    pub messages: Vec<Message>,
+}
+
+add_base64! { MessageThread }
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NewMessage {
+    pub recipients: Vec<UserID>,
+    pub subject: String,
+    pub body: String,
+}
+
+add_base64! { NewMessage }
+
+pub fn unread_count(threads: &[ThreadSummary]) -> usize {
+    threads.iter().filter(|thread| thread.unread).count()
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{MaterialID, SectionID};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct NotificationID(pub u64);
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NotificationKind {
+    Grade,
+    Comment,
+    Material,
+    Other,
+}
+
+impl NotificationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationKind::Grade => "Grade",
+            NotificationKind::Comment => "Comment",
+            NotificationKind::Material => "New material",
+            NotificationKind::Other => "Notification",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Notification {
+    pub id: NotificationID,
+    pub kind: NotificationKind,
+    pub text: String,
+    pub section: Option<SectionID>,
+    pub material: Option<MaterialID>,
+    pub posted: String,
+}
+
+/// Schoology only tracks "seen" for the whole dropdown, so read state is kept per notification locally.
+pub fn unread_count(notifications: &[Notification], read: &HashSet<NotificationID>) -> usize {
+    notifications
+        .iter()
+        .filter(|notification| !read.contains(&notification.id))
+        .count()
+}
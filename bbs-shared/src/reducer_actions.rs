@@ -3,10 +3,17 @@ use std::rc::Rc;
 use lazy_static::__Deref;
 use yew::Reducible;
 
-use crate::data::{ClassEntry, Assignment};
+use crate::data::{ClassEntry, ClassRoster, Assignment, FeedItem, Instructor, MemberPage, Submission, UpcomingItem};
+use crate::grades::GradeReport;
+use crate::settings::Settings;
+use crate::events::UploadProgress;
+use crate::messages::{MessageThread, ThreadID, ThreadSummary};
+use crate::notifications::Notification;
+use crate::accounts::{AccountList, ParentInfo};
+use crate::schedule::DayFilter;
 use crate::errors::LoginError;
-use crate::{ ClassID, MaterialID };
-use crate::PageState;
+use crate::{ ClassID, MaterialID, SectionID };
+use crate::{PageState, state::ClassPageTab};
 use crate::FrontendData;
 
 #[derive(Debug, Clone)]
@@ -16,12 +23,16 @@ pub enum StateUpdateAction {
     LogIn,
     ReturnLogin,
     SetUname(String),
-    SetPassw(String),
+    SetCode(String),
     ToMain,
-    SetDayFilter(usize),
+    SetDayFilter(DayFilter),
     LoadClass(ClassID),
     ToClass(ClassID),
-    ToClassItem(MaterialID)
+    SetClassTab(ClassPageTab),
+    ToClassItem(MaterialID),
+    ToInbox,
+    OpenThread(ThreadID),
+    ComposeMessage,
 }
 
 use StateUpdateAction::*;
@@ -32,34 +43,47 @@ impl Reducible for PageState {
         match action {
             ToLogin => Rc::new(PageState::Login {
                 username: String::new(),
-                password: String::new(),
+            }),
+            // a code challenge isn't a failure, it's the next step
+            FailLogin(LoginError::CodeRequired { hint }) => Rc::new(PageState::VerifyingCode {
+                username: self.as_login_username().cloned().unwrap_or_default(),
+                hint,
+                code: String::new(),
+                rejected: false,
+            }),
+            FailLogin(LoginError::InvalidCode { hint }) => Rc::new(PageState::VerifyingCode {
+                username: self.as_login_username().cloned().unwrap_or_default(),
+                hint,
+                code: String::new(),
+                rejected: true,
             }),
             FailLogin(reason) => Rc::new(PageState::LoginFailed {
                 username: self.as_login_username().cloned().unwrap_or_default(),
-                password: self.as_login_password().cloned().unwrap_or_default(),
                 reason,
             }),
             LogIn => Rc::new(PageState::LoggingIn {
                 username: self.as_login_username().cloned().unwrap_or_default(),
-                password: self.as_login_password().cloned().unwrap_or_default(),
             }),
             ReturnLogin => Rc::new(PageState::Login {
                 username: self.as_login_username().cloned().unwrap_or_default(),
-                password: self.as_login_password().cloned().unwrap_or_default(),
             }),
             SetUname(username) => Rc::new(PageState::Login {
                 username,
-                password: self.as_login_password().cloned().unwrap_or_default(),
-            }),
-            SetPassw(password) => Rc::new(PageState::Login {
-                username: self.as_login_username().cloned().unwrap_or_default(),
-                password,
             }),
+            SetCode(code) => match self.deref() {
+                PageState::VerifyingCode { username, hint, .. } => Rc::new(PageState::VerifyingCode {
+                    username: username.clone(),
+                    hint: hint.clone(),
+                    code,
+                    rejected: false,
+                }),
+                _ => self,
+            },
             ToMain => Rc::new(PageState::Main {
-                day: None
+                filter: None
             }),
-            SetDayFilter(day) => Rc::new(PageState::Main {
-                day: Some(day)
+            SetDayFilter(filter) => Rc::new(PageState::Main {
+                filter: Some(filter)
             }),
             LoadClass(class_id) => Rc::new(PageState::LoadingClass {
                 class_id,
@@ -67,7 +91,16 @@ impl Reducible for PageState {
             ToClass(class_id) => Rc::new(PageState::ClassPage {
                 id: class_id,
                 expanded_folders: vec![],
+                tab: ClassPageTab::Materials,
             }),
+            SetClassTab(tab) => match self.deref() {
+                PageState::ClassPage { id, expanded_folders, .. } => Rc::new(PageState::ClassPage {
+                    id: *id,
+                    expanded_folders: expanded_folders.clone(),
+                    tab,
+                }),
+                _ => self,
+            },
             ToClassItem(class_item_id) => Rc::new(PageState::ClassItemPage {
                 id: class_item_id,
                 class_id: if let Self::ClassPage{ id, .. } = self.deref() {
@@ -77,6 +110,18 @@ impl Reducible for PageState {
                 },
                 page_specific_data: (),
             }),
+            ToInbox => Rc::new(PageState::Inbox {
+                thread: None,
+                composing: false,
+            }),
+            OpenThread(thread) => Rc::new(PageState::Inbox {
+                thread: Some(thread),
+                composing: false,
+            }),
+            ComposeMessage => Rc::new(PageState::Inbox {
+                thread: None,
+                composing: true,
+            }),
         }
     }
 }
@@ -85,8 +130,32 @@ impl Reducible for PageState {
 pub enum DataUpdateAction {
     ClearClassListing,
     SetClassListing(Vec<ClassEntry>),
+    SetClassInstructors(SectionID, Vec<Instructor>),
     ClearClassPageInfo,
     SetClassPageInfo(Vec<Assignment>),
+    SetSubmission(MaterialID, Submission),
+    SetHomeFeed(Vec<FeedItem>),
+    SetGrades(Vec<GradeReport>),
+    SetSettings(Settings),
+    StartUpload(u64),
+    /// Ignored unless it belongs to the upload that's currently running.
+    SetUploadProgress(UploadProgress),
+    FinishUpload(u64),
+    /// Starts a page of `query`'s results, dropping the loaded members if the search changed.
+    RequestMemberPage(SectionID, String, u32),
+    /// Ignored unless it's the page last requested for the same search.
+    AddMemberPage(SectionID, String, u32, MemberPage),
+    FailMemberPage(SectionID, u32),
+    SetInbox(Vec<ThreadSummary>),
+    SetMessageThread(MessageThread),
+    SetNotifications(Vec<Notification>),
+    SetUpcoming(Vec<UpcomingItem>),
+    SetAccounts(AccountList),
+    SetChildren(ParentInfo),
+    AssetReady(String),
+    /// Drops everything fetched for the previous account. The account list stays, and so do settings until the
+    /// new account's replace them.
+    ClearAccountData,
 }
 
 use DataUpdateAction::*;
@@ -99,30 +168,232 @@ impl Reducible for FrontendData {
                 *self.classes.borrow_mut() = None;
                 Rc::new(FrontendData {
                     classes: self.classes.new_inc_clone(),
-                    curr_class_data: self.curr_class_data.clone(),
+                    ..(*self).clone()
                 })
             },
             SetClassListing(class_entries) => {
                 *self.classes.borrow_mut() = Some(class_entries);
                 Rc::new(FrontendData {
                     classes: self.classes.new_inc_clone(),
-                    curr_class_data: self.curr_class_data.clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetClassInstructors(section, instructors) => {
+                if let Some(classes) = self.classes.borrow_mut().as_mut() {
+                    classes
+                        .iter_mut()
+                        .filter(|entry| entry.section_nid == section)
+                        .for_each(|entry| entry.instructors = Some(instructors.clone()));
+                }
+                Rc::new(FrontendData {
+                    classes: self.classes.new_inc_clone(),
+                    ..(*self).clone()
                 })
             },
             ClearClassPageInfo => {
                 *self.curr_class_data.borrow_mut() = None;
                 Rc::new(FrontendData {
-                    classes: self.classes.clone(),
                     curr_class_data: self.curr_class_data.new_inc_clone(),
+                    ..(*self).clone()
                 })
             },
             SetClassPageInfo(class_page_data) => {
                 *self.curr_class_data.borrow_mut() = Some(class_page_data);
                 Rc::new(FrontendData {
-                    classes: self.classes.clone(),
                     curr_class_data: self.curr_class_data.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetSubmission(material_id, submission) => {
+                if let Some(materials) = self.curr_class_data.borrow_mut().as_mut() {
+                    materials
+                        .iter_mut()
+                        .filter(|material| material.id == material_id)
+                        .for_each(|material| material.submission = Some(submission.clone()));
+                }
+                Rc::new(FrontendData {
+                    curr_class_data: self.curr_class_data.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetHomeFeed(feed_items) => {
+                *self.home_feed.borrow_mut() = Some(feed_items);
+                Rc::new(FrontendData {
+                    home_feed: self.home_feed.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetGrades(grade_reports) => {
+                *self.grades.borrow_mut() = Some(grade_reports);
+                Rc::new(FrontendData {
+                    grades: self.grades.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetSettings(settings) => {
+                *self.settings.borrow_mut() = Some(settings);
+                Rc::new(FrontendData {
+                    settings: self.settings.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            StartUpload(upload) => {
+                *self.active_upload.borrow_mut() = Some(upload);
+                *self.upload_progress.borrow_mut() = None;
+                Rc::new(FrontendData {
+                    active_upload: self.active_upload.new_inc_clone(),
+                    upload_progress: self.upload_progress.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetUploadProgress(progress) => {
+                if *self.active_upload.borrow() != Some(progress.upload) {
+                    return self;
+                }
+
+                *self.upload_progress.borrow_mut() = Some(progress);
+                Rc::new(FrontendData {
+                    upload_progress: self.upload_progress.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            FinishUpload(upload) => {
+                if *self.active_upload.borrow() != Some(upload) {
+                    return self;
+                }
+
+                *self.active_upload.borrow_mut() = None;
+                *self.upload_progress.borrow_mut() = None;
+                Rc::new(FrontendData {
+                    active_upload: self.active_upload.new_inc_clone(),
+                    upload_progress: self.upload_progress.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            RequestMemberPage(section, query, page) => {
+                let mut rosters = self.rosters.borrow_mut();
+                let roster = rosters
+                    .get_or_insert_with(Default::default)
+                    .entry(section)
+                    .or_default();
+                if roster.query != query {
+                    *roster = ClassRoster {
+                        query,
+                        ..Default::default()
+                    };
+                }
+                roster.pending = Some(page);
+                drop(rosters);
+                Rc::new(FrontendData {
+                    rosters: self.rosters.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            AddMemberPage(section, query, page, member_page) => {
+                let accepted = match self.rosters.borrow_mut().as_mut().and_then(|rosters| rosters.get_mut(&section)) {
+                    Some(roster) if roster.query == query && roster.pending == Some(page) => {
+                        roster.pending = None;
+                        // a first page means a refresh, so drop whatever was loaded before
+                        if page <= 1 {
+                            roster.members.clear();
+                            roster.pages_loaded = 0;
+                        }
+                        // anything else out of order is a page we already have
+                        if page == roster.pages_loaded + 1 {
+                            roster.members.extend(member_page.members);
+                            roster.pages_loaded = page;
+                            roster.next_page = member_page.has_next.then(|| page + 1);
+                        }
+                        true
+                    },
+                    _ => false,
+                };
+                if !accepted {
+                    return self;
+                }
+                Rc::new(FrontendData {
+                    rosters: self.rosters.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            FailMemberPage(section, page) => {
+                let cleared = match self.rosters.borrow_mut().as_mut().and_then(|rosters| rosters.get_mut(&section)) {
+                    Some(roster) if roster.pending == Some(page) => {
+                        roster.pending = None;
+                        true
+                    },
+                    _ => false,
+                };
+                if !cleared {
+                    return self;
+                }
+                Rc::new(FrontendData {
+                    rosters: self.rosters.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetNotifications(notifications) => {
+                *self.notifications.borrow_mut() = Some(notifications);
+                Rc::new(FrontendData {
+                    notifications: self.notifications.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetAccounts(accounts) => {
+                *self.accounts.borrow_mut() = Some(accounts);
+                Rc::new(FrontendData {
+                    accounts: self.accounts.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetChildren(children) => {
+                *self.children.borrow_mut() = Some(children);
+                Rc::new(FrontendData {
+                    children: self.children.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            AssetReady(asset) => {
+                self.ready_assets.borrow_mut().get_or_insert_with(Default::default).insert(asset);
+                Rc::new(FrontendData {
+                    ready_assets: self.ready_assets.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            ClearAccountData => Rc::new(FrontendData {
+                settings: self.settings.new_inc_clone(),
+                accounts: self.accounts.new_inc_clone(),
+                ..FrontendData::empty()
+            }),
+            SetUpcoming(upcoming) => {
+                *self.upcoming.borrow_mut() = Some(upcoming);
+                Rc::new(FrontendData {
+                    upcoming: self.upcoming.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetInbox(threads) => {
+                *self.inbox.borrow_mut() = Some(threads);
+                Rc::new(FrontendData {
+                    inbox: self.inbox.new_inc_clone(),
+                    ..(*self).clone()
+                })
+            },
+            SetMessageThread(thread) => {
+                // reading a thread on schoology marks it read, so mirror that in the listing
+                if let Some(threads) = self.inbox.borrow_mut().as_mut() {
+                    threads
+                        .iter_mut()
+                        .filter(|summary| summary.id == thread.id)
+                        .for_each(|summary| summary.unread = false);
+                }
+                *self.message_thread.borrow_mut() = Some(thread);
+                Rc::new(FrontendData {
+                    inbox: self.inbox.new_inc_clone(),
+                    message_thread: self.message_thread.new_inc_clone(),
+                    ..(*self).clone()
                 })
             },
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,214 @@
+use std::{collections::{HashMap, HashSet}, fmt::Display};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::{Serialize, Deserialize};
+
+/// A day of the rotation, `LetterDay(0)` being the "A" day.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LetterDay(pub u8);
+
+impl LetterDay {
+    pub fn from_letter(letter: char) -> Option<Self> {
+        letter
+            .is_ascii_uppercase()
+            .then(|| LetterDay(letter as u8 - b'A'))
+    }
+
+    pub fn letter(&self) -> char {
+        (b'A' + self.0) as char
+    }
+}
+
+impl Display for LetterDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.letter())
+    }
+}
+
+/// What the main page is filtered by: a real calendar date, or a letter picked straight from a section's indicators.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DayFilter {
+    Date(NaiveDate),
+    Letter(LetterDay),
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PeriodTime {
+    pub period: u64,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// The period times for one kind of day, e.g. "Regular" or "Early dismissal".
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BellSchedule {
+    pub name: String,
+    pub periods: Vec<PeriodTime>,
+}
+
+impl BellSchedule {
+    pub fn period(&self, period: u64) -> Option<&PeriodTime> {
+        self.periods.iter().find(|time| time.period == period)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// Letters in the order they rotate through.
+    pub rotation: Vec<LetterDay>,
+    /// The first day of the rotation, which gets `rotation[0]`.
+    /// Without one, letters fall back to weekdays (Monday is A).
+    pub start_date: Option<NaiveDate>,
+    /// Weekdays school meets on, starting with Monday.
+    pub school_days: [bool; 7],
+    /// No school, and the rotation picks up where it left off afterwards.
+    pub holidays: HashSet<NaiveDate>,
+    /// No school, but the letter is used up anyway (e.g. snow days that the district doesn't make up).
+    pub skipped_days: HashSet<NaiveDate>,
+    /// The first one is used on any day without an entry in `day_types`.
+    pub bell_schedules: Vec<BellSchedule>,
+    /// Dates that run on a different bell schedule, by schedule name.
+    pub day_types: HashMap<NaiveDate, String>,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            rotation: (0..5).map(LetterDay).collect(),
+            start_date: None,
+            school_days: [true, true, true, true, true, false, false],
+            holidays: HashSet::new(),
+            skipped_days: HashSet::new(),
+            bell_schedules: Vec::new(),
+            day_types: HashMap::new(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    fn is_school_weekday(&self, date: NaiveDate) -> bool {
+        self.school_days[date.weekday().num_days_from_monday() as usize]
+    }
+
+    /// How many days in `from..to` move the rotation on. Counted by whole weeks rather than day by day, since
+    /// the main page asks for every class on every render.
+    fn advancing_days(&self, from: NaiveDate, to: NaiveDate) -> usize {
+        let days = (to - from).num_days().max(0);
+        let full_weeks = days / 7;
+        let school_days_per_week = self.school_days.iter().filter(|meets| **meets).count();
+
+        let rest = (full_weeks * 7..days)
+            .map(|offset| from + Duration::days(offset))
+            .filter(|day| self.is_school_weekday(*day))
+            .count();
+        let holidays = self.holidays
+            .iter()
+            .filter(|holiday| from <= **holiday && **holiday < to && self.is_school_weekday(**holiday))
+            .count();
+
+        full_weeks as usize * school_days_per_week + rest - holidays
+    }
+
+    /// The letter day `date` falls on, or `None` if there's no school that day.
+    pub fn letter_for(&self, date: NaiveDate) -> Option<LetterDay> {
+        if !self.is_school_weekday(date) || self.holidays.contains(&date) || self.skipped_days.contains(&date) {
+            return None;
+        }
+
+        let start_date = match self.start_date {
+            Some(start_date) => start_date,
+            None => return match date.weekday() {
+                Weekday::Sat | Weekday::Sun => None,
+                weekday => Some(LetterDay(weekday.num_days_from_monday() as u8)),
+            },
+        };
+
+        if date < start_date || self.rotation.is_empty() {
+            return None;
+        }
+
+        let elapsed = self.advancing_days(start_date, date);
+
+        self.rotation.get(elapsed % self.rotation.len()).copied()
+    }
+
+    pub fn bell_schedule_for(&self, date: NaiveDate) -> Option<&BellSchedule> {
+        self.letter_for(date)?;
+
+        match self.day_types.get(&date) {
+            Some(name) => self.bell_schedules.iter().find(|schedule| &schedule.name == name),
+            None => self.bell_schedules.first(),
+        }
+    }
+
+    pub fn period_time(&self, date: NaiveDate, period: u64) -> Option<PeriodTime> {
+        self.bell_schedule_for(date)?.period(period).copied()
+    }
+
+    pub fn resolve(&self, filter: DayFilter) -> Option<LetterDay> {
+        match filter {
+            DayFilter::Date(date) => self.letter_for(date),
+            DayFilter::Letter(letter) => Some(letter),
+        }
+    }
+
+    /// The next date on or after `from` that has school, looking at most a year ahead.
+    pub fn next_school_day(&self, from: NaiveDate) -> Option<(NaiveDate, LetterDay)> {
+        (0..366)
+            .map(|offset| from + Duration::days(offset))
+            .find_map(|date| Some((date, self.letter_for(date)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// The day-by-day walk `advancing_days` replaced.
+    fn walked_days(config: &ScheduleConfig, from: NaiveDate, to: NaiveDate) -> usize {
+        from.iter_days()
+            .take_while(|day| *day < to)
+            .filter(|day| config.is_school_weekday(*day) && !config.holidays.contains(day))
+            .count()
+    }
+
+    fn date(offset: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2022, 8, 29).unwrap() + Duration::days(offset)
+    }
+
+    #[test]
+    fn rotates_past_holidays_and_skipped_days() {
+        let config = ScheduleConfig {
+            start_date: Some(date(0)),
+            holidays: [date(1)].into_iter().collect(),
+            skipped_days: [date(2)].into_iter().collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(config.letter_for(date(0)), Some(LetterDay(0)));
+        assert_eq!(config.letter_for(date(1)), None);
+        assert_eq!(config.letter_for(date(2)), None);
+        assert_eq!(config.letter_for(date(3)), Some(LetterDay(2)));
+        assert_eq!(config.letter_for(date(5)), None);
+        assert_eq!(config.letter_for(date(7)), Some(LetterDay(4)));
+        assert_eq!(config.letter_for(date(8)), Some(LetterDay(0)));
+    }
+
+    proptest! {
+        #[test]
+        fn counts_like_walking(
+            school_days in prop::array::uniform7(any::<bool>()),
+            holidays in prop::collection::hash_set((0..400i64).prop_map(date), 0..20),
+            from in 0..200i64,
+            length in 0..400i64,
+        ) {
+            let config = ScheduleConfig { school_days, holidays, ..Default::default() };
+            let (from, to) = (date(from), date(from + length));
+
+            prop_assert_eq!(config.advancing_days(from, to), walked_days(&config, from, to));
+        }
+    }
+}
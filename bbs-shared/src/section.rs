@@ -0,0 +1,300 @@
+//! Parsing for section titles, which is where schools put a class's period and meeting days.
+//!
+//! ```text
+//! title   := ws period ws lab? ws '(' ws days ws ')' ws
+//! period  := number (ws '-' ws number)? | name
+//! lab     := "L" | "Lab"
+//! days    := range (ws ',' ws range)*
+//! range   := letter (ws '-' ws letter)?
+//! name    := any text up to '(' that doesn't start with a digit, e.g. "Lunch" or "Study Hall"
+//! letter  := 'A'..='Z', either case
+//! ```
+
+use std::{collections::BTreeSet, fmt::Display, iter::Peekable, str::CharIndices};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{data::SectionDataGuts, errors::SectionParseError, schedule::LetterDay};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Period {
+    /// One period, or a block running from `first` through `last`.
+    Numbered { first: u64, last: u64 },
+    Named(String),
+}
+
+impl Period {
+    /// The first and last period numbers, for looking the section up in a bell schedule.
+    pub fn numbers(&self) -> Option<(u64, u64)> {
+        match self {
+            Period::Numbered { first, last } => Some((*first, *last)),
+            Period::Named(_) => None,
+        }
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Period::Numbered { first, last } if first == last => write!(f, "{}", first),
+            Period::Numbered { first, last } => write!(f, "{}-{}", first, last),
+            Period::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map(|(idx, _)| *idx).unwrap_or(self.source.len())
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(expected);
+        if found {
+            self.chars.next();
+        }
+        found
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position();
+        while self.peek().map_or(false, &predicate) {
+            self.chars.next();
+        }
+        let end = self.position();
+        &self.source[start..end]
+    }
+
+    fn number(&mut self) -> Result<u64, SectionParseError> {
+        self.skip_whitespace();
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.parse().map_err(|_| SectionParseError::InvalidPeriod(digits.to_owned()))
+    }
+
+    fn period(&mut self) -> Result<(Period, bool), SectionParseError> {
+        self.skip_whitespace();
+
+        if !self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            let name = self.take_while(|c| c != '(').trim();
+            return match name {
+                "" => Err(SectionParseError::MissingPeriod),
+                name => Ok((Period::Named(name.to_owned()), false)),
+            };
+        }
+
+        let first = self.number()?;
+        let last = if self.eat('-') { self.number()? } else { first };
+        if last < first {
+            return Err(SectionParseError::BackwardsPeriods(first, last));
+        }
+
+        self.skip_whitespace();
+        let lab = match self.take_while(char::is_alphabetic) {
+            "" => false,
+            suffix if suffix.eq_ignore_ascii_case("l") || suffix.eq_ignore_ascii_case("lab") => true,
+            suffix => return Err(SectionParseError::UnexpectedText(suffix.to_owned())),
+        };
+
+        Ok((Period::Numbered { first, last }, lab))
+    }
+
+    fn letter(&mut self) -> Result<LetterDay, SectionParseError> {
+        self.skip_whitespace();
+        let letter = self.chars.next().map(|(_, c)| c).ok_or(SectionParseError::MissingCloseParen)?;
+        LetterDay::from_letter(letter.to_ascii_uppercase()).ok_or(SectionParseError::InvalidDay(letter))
+    }
+
+    fn days(&mut self) -> Result<BTreeSet<LetterDay>, SectionParseError> {
+        let mut days = BTreeSet::new();
+
+        if self.eat(')') {
+            return Err(SectionParseError::NoDays);
+        }
+
+        loop {
+            let start = self.letter()?;
+            let end = if self.eat('-') { self.letter()? } else { start };
+            if end < start {
+                return Err(SectionParseError::BackwardsDays(start.letter(), end.letter()));
+            }
+            days.extend((start.0..=end.0).map(LetterDay));
+
+            if self.eat(')') {
+                return Ok(days);
+            }
+            if !self.eat(',') {
+                self.skip_whitespace();
+                return Err(match self.peek() {
+                    Some(c) => SectionParseError::UnexpectedText(c.to_string()),
+                    None => SectionParseError::MissingCloseParen,
+                });
+            }
+        }
+    }
+
+    fn title(&mut self) -> Result<SectionDataGuts, SectionParseError> {
+        let (period, lab) = self.period()?;
+
+        if !self.eat('(') {
+            return Err(SectionParseError::MissingOpenParen);
+        }
+        let days = self.days()?;
+
+        self.skip_whitespace();
+        let rest = &self.source[self.position()..];
+        if !rest.is_empty() {
+            return Err(SectionParseError::UnexpectedText(rest.to_owned()));
+        }
+
+        Ok(SectionDataGuts::Good { days, period, lab })
+    }
+}
+
+pub fn parse_section(s: &str) -> Result<SectionDataGuts, SectionParseError> {
+    if s.trim().is_empty() {
+        return Err(SectionParseError::Empty);
+    }
+
+    Parser::new(s).title()
+}
+
+/// Writes a section back out in the canonical form `parse_section` reads, e.g. `3-4L(A,C-E)`.
+pub fn format_section(period: &Period, lab: bool, days: &BTreeSet<LetterDay>) -> String {
+    let mut ranges: Vec<(LetterDay, LetterDay)> = Vec::new();
+
+    for &day in days {
+        match ranges.last_mut() {
+            Some((_, end)) if end.0 + 1 == day.0 => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+
+    let ranges: Vec<_> = ranges
+        .into_iter()
+        .map(|(start, end)| if start == end {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, end)
+        })
+        .collect();
+
+    format!("{}{}({})", period, if lab { "L" } else { "" }, ranges.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn good(period: Period, lab: bool, letters: &str) -> Result<SectionDataGuts, SectionParseError> {
+        let days = letters.chars().map(|letter| LetterDay::from_letter(letter).unwrap()).collect();
+        Ok(SectionDataGuts::Good { days, period, lab })
+    }
+
+    fn numbered(first: u64, last: u64) -> Period {
+        Period::Numbered { first, last }
+    }
+
+    fn named(name: &str) -> Period {
+        Period::Named(name.to_owned())
+    }
+
+    #[test]
+    fn parses_titles() {
+        assert_eq!(parse_section("3(A)"), good(numbered(3, 3), false, "A"));
+        assert_eq!(parse_section("3-4(A,C)"), good(numbered(3, 4), false, "AC"));
+        assert_eq!(parse_section("P(A-D,E)"), good(named("P"), false, "ABCDE"));
+        assert_eq!(parse_section("Lunch (A-E)"), good(named("Lunch"), false, "ABCDE"));
+        assert_eq!(parse_section("Study Hall(b-d)"), good(named("Study Hall"), false, "BCD"));
+        assert_eq!(parse_section("2(A,F-H)"), good(numbered(2, 2), false, "AFGH"));
+        assert_eq!(parse_section("7(z)"), good(numbered(7, 7), false, "Z"));
+    }
+
+    #[test]
+    fn parses_lab_suffixes() {
+        assert_eq!(parse_section("3L(A)"), good(numbered(3, 3), true, "A"));
+        assert_eq!(parse_section("3l(A)"), good(numbered(3, 3), true, "A"));
+        assert_eq!(parse_section("3 Lab (A)"), good(numbered(3, 3), true, "A"));
+        assert_eq!(parse_section("5-6lab(B,D)"), good(numbered(5, 6), true, "BD"));
+    }
+
+    #[test]
+    fn ignores_whitespace() {
+        let expected = good(numbered(3, 4), false, "AC");
+        assert_eq!(parse_section(" 3 - 4 ( A , C ) "), expected);
+        assert_eq!(parse_section("3-4\t(A,\nC)"), expected);
+        assert_eq!(parse_section("  Lunch  ( A - E )"), good(named("Lunch"), false, "ABCDE"));
+    }
+
+    #[test]
+    fn reports_each_error() {
+        assert_eq!(parse_section(""), Err(SectionParseError::Empty));
+        assert_eq!(parse_section("   "), Err(SectionParseError::Empty));
+        assert_eq!(parse_section("(A)"), Err(SectionParseError::MissingPeriod));
+        assert_eq!(parse_section("3-(A)"), Err(SectionParseError::InvalidPeriod(String::new())));
+        assert_eq!(
+            parse_section("99999999999999999999(A)"),
+            Err(SectionParseError::InvalidPeriod("99999999999999999999".to_owned())),
+        );
+        assert_eq!(parse_section("4-3(A)"), Err(SectionParseError::BackwardsPeriods(4, 3)));
+        assert_eq!(parse_section("3"), Err(SectionParseError::MissingOpenParen));
+        assert_eq!(parse_section("Lunch"), Err(SectionParseError::MissingOpenParen));
+        assert_eq!(parse_section("3(A"), Err(SectionParseError::MissingCloseParen));
+        assert_eq!(parse_section("3(A,"), Err(SectionParseError::MissingCloseParen));
+        assert_eq!(parse_section("3()"), Err(SectionParseError::NoDays));
+        assert_eq!(parse_section("3(é)"), Err(SectionParseError::InvalidDay('é')));
+        assert_eq!(parse_section("3(1)"), Err(SectionParseError::InvalidDay('1')));
+        assert_eq!(parse_section("3(C-A)"), Err(SectionParseError::BackwardsDays('C', 'A')));
+        assert_eq!(parse_section("3X(A)"), Err(SectionParseError::UnexpectedText("X".to_owned())));
+        assert_eq!(parse_section("3(A B)"), Err(SectionParseError::UnexpectedText("B".to_owned())));
+        assert_eq!(parse_section("3(A) extra"), Err(SectionParseError::UnexpectedText("extra".to_owned())));
+    }
+
+    fn period() -> impl Strategy<Value = Period> {
+        prop_oneof![
+            (0..1000u64, 0..4u64).prop_map(|(first, length)| numbered(first, first + length)),
+            // names can't start with a digit or hold a paren, and lose their outer whitespace
+            "[A-Za-z][A-Za-z ]{0,12}[A-Za-z]".prop_map(Period::Named),
+        ]
+    }
+
+    fn days() -> impl Strategy<Value = BTreeSet<LetterDay>> {
+        // a section meets on at least one day
+        prop::collection::btree_set((0..26u8).prop_map(LetterDay), 1..8)
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(period in period(), lab in any::<bool>(), days in days()) {
+            // a lab suffix after a name just reads as part of the name
+            let lab = lab && period.numbers().is_some();
+            let formatted = format_section(&period, lab, &days);
+
+            prop_assert_eq!(parse_section(&formatted), Ok(SectionDataGuts::Good { days, period, lab }));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{add_base64, gpa::GpaConfig, notifications::NotificationID, schedule::ScheduleConfig};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SortMode {
+    Period,
+    Name,
+    NextDue,
+    Grade,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [SortMode::Period, SortMode::Name, SortMode::NextDue, SortMode::Grade];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortMode::Period => "Period",
+            SortMode::Name => "Name",
+            SortMode::NextDue => "Next due",
+            SortMode::Grade => "Grade",
+        }
+    }
+}
+
+impl Default for SortMode {
+    fn default() -> Self {
+        SortMode::Period
+    }
+}
+
+/// Where the backend keeps cookies and remembered passwords. Only read at startup.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionStoreKind {
+    Keyring,
+    EncryptedFile,
+    Memory,
+}
+
+impl Default for SessionStoreKind {
+    // dev builds are re-signed on every rebuild, which makes the keyring prompt each launch
+    #[cfg(debug_assertions)]
+    fn default() -> Self {
+        SessionStoreKind::EncryptedFile
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn default() -> Self {
+        SessionStoreKind::Keyring
+    }
+}
+
+/// User preferences persisted by the backend between launches. The frontend only ever sees the active
+/// account's; the backend keeps everything but `session_store` separately for each account.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub gpa: GpaConfig,
+    pub read_notifications: HashSet<NotificationID>,
+    pub schedule: ScheduleConfig,
+    pub sort_mode: SortMode,
+    pub session_store: SessionStoreKind,
+}
+
+add_base64! { Settings }
+
+/// The part of `Settings` that belongs to one account.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AccountSettings {
+    pub gpa: GpaConfig,
+    pub read_notifications: HashSet<NotificationID>,
+    pub schedule: ScheduleConfig,
+    pub sort_mode: SortMode,
+}
+
+impl Settings {
+    pub fn join(session_store: SessionStoreKind, account: AccountSettings) -> Self {
+        let AccountSettings { gpa, read_notifications, schedule, sort_mode } = account;
+        Self { gpa, read_notifications, schedule, sort_mode, session_store }
+    }
+
+    pub fn split(self) -> (SessionStoreKind, AccountSettings) {
+        let Settings { gpa, read_notifications, schedule, sort_mode, session_store } = self;
+        (session_store, AccountSettings { gpa, read_notifications, schedule, sort_mode })
+    }
+}
@@ -1,24 +1,30 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{ClassID, MaterialID, errors::LoginError};
+use crate::{ClassID, MaterialID, errors::LoginError, messages::ThreadID, schedule::DayFilter};
 
 #[derive(Clone, Debug,Serialize, Deserialize, PartialEq, Eq)]
 pub enum PageState {
     Login {
         username: String,
-        password: String,
     },
     LoggingIn {
         username: String,
-        password: String,
     },
     LoginFailed {
         username: String,
-        password: String,
         reason: LoginError,
     },
+    /// The password was accepted but Schoology wants a code from email or an authenticator app.
+    VerifyingCode {
+        username: String,
+        /// Schoology's own instructions, e.g. where the code was sent.
+        hint: String,
+        code: String,
+        /// The last code was wrong.
+        rejected: bool,
+    },
     Main {
-        day: Option<usize>
+        filter: Option<DayFilter>
     },
     LoadingClass {
         class_id: ClassID,
@@ -26,12 +32,24 @@ pub enum PageState {
     ClassPage {
         id: ClassID,
         expanded_folders: Vec<MaterialID>,
+        tab: ClassPageTab,
     },
     ClassItemPage {
         class_id: ClassID,
         id: MaterialID,
         page_specific_data: (),
     },
+    Inbox {
+        thread: Option<ThreadID>,
+        composing: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ClassPageTab {
+    Materials,
+    Grades,
+    People,
 }
 
 impl PageState {
@@ -43,19 +61,15 @@ impl PageState {
         }
     }
     pub fn as_login_username(&self) -> Option<&String> {
-        if let PageState::Login { username, .. } | PageState::LoginFailed { username, .. } = self {
+        if let PageState::Login { username, .. }
+            | PageState::LoggingIn { username, .. }
+            | PageState::LoginFailed { username, .. }
+            | PageState::VerifyingCode { username, .. } = self {
             Some(username)
         } else {
             None
         }
     }
-    pub fn as_login_password(&self) -> Option<&String> {
-        if let PageState::Login { password, .. } | PageState::LoginFailed { password, .. } = self {
-            Some(password)
-        } else {
-            None
-        }
-    }
     pub fn is_main(&self) -> bool {
         if let PageState::Main { .. } = self {
             true
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::grades::{GradingPeriod, GradedItem};
+
+/// Identifies an item within a grading period as `(category index, item index)`.
+pub type ItemKey = (usize, usize);
+
+/// Hypothetical scores layered over a scraped grading period.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WhatIf {
+    pub scores: HashMap<ItemKey, f64>,
+}
+
+impl WhatIf {
+    pub fn score_of(&self, key: ItemKey, item: &GradedItem) -> Option<f64> {
+        self.scores.get(&key).copied().or(item.score)
+    }
+
+    /// Points earned and points possible across every scored item in a category.
+    fn category_points(&self, period: &GradingPeriod, category_idx: usize) -> Option<(f64, f64)> {
+        let category = period.categories.get(category_idx)?;
+
+        let (earned, possible) = category.items
+            .iter()
+            .enumerate()
+            .filter_map(|(item_idx, item)| Some((self.score_of((category_idx, item_idx), item)?, item.max?)))
+            .fold((0.0, 0.0), |(earned, possible), (score, max)| (earned + score, possible + max));
+
+        if possible > 0.0 {
+            Some((earned, possible))
+        } else {
+            None
+        }
+    }
+
+    pub fn category_percent(&self, period: &GradingPeriod, category_idx: usize) -> Option<f64> {
+        let (earned, possible) = self.category_points(period, category_idx)?;
+        Some(earned / possible * 100.0)
+    }
+
+    /// Weighted categories are renormalized over the ones that have scores,
+    /// which is how Schoology handles empty categories. Unweighted classes use total points.
+    pub fn period_percent(&self, period: &GradingPeriod) -> Option<f64> {
+        let is_weighted = period.categories.iter().any(|category| category.weight.is_some());
+
+        if is_weighted {
+            let (weighted_sum, total_weight) = period.categories
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, category)| Some((self.category_percent(period, idx)?, category.weight?)))
+                .fold((0.0, 0.0), |(sum, total), (percent, weight)| (sum + percent * weight, total + weight));
+
+            if total_weight > 0.0 {
+                Some(weighted_sum / total_weight)
+            } else {
+                None
+            }
+        } else {
+            let (earned, possible) = (0..period.categories.len())
+                .filter_map(|idx| self.category_points(period, idx))
+                .fold((0.0, 0.0), |(earned, possible), (category_earned, category_possible)| (earned + category_earned, possible + category_possible));
+
+            if possible > 0.0 {
+                Some(earned / possible * 100.0)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// The raw score needed on `target` for the period grade to reach `goal_percent`.
+    ///
+    /// The period grade is linear in any single item's score, so two samples are enough to solve for it.
+    /// The result may be negative (goal already secured) or above the item's max (goal out of reach).
+    pub fn required_score(&self, period: &GradingPeriod, target: ItemKey, goal_percent: f64) -> Option<f64> {
+        let max = period.categories
+            .get(target.0)?
+            .items
+            .get(target.1)?
+            .max
+            .filter(|max| *max > 0.0)?;
+
+        let mut sample = self.clone();
+
+        sample.scores.insert(target, 0.0);
+        let at_zero = sample.period_percent(period)?;
+
+        sample.scores.insert(target, max);
+        let at_max = sample.period_percent(period)?;
+
+        if (at_max - at_zero).abs() < f64::EPSILON {
+            None
+        } else {
+            Some((goal_percent - at_zero) / (at_max - at_zero) * max)
+        }
+    }
+}
@@ -9,8 +9,48 @@ export async function invokeGetClassListing() {
     return await invoke("get_class_listing");
 }
 
-export async function invokeSetCredentials(username, password) {
-    return await invoke("set_credentials", { username, password });
+export async function invokeSetCredentials(username, password, remember) {
+    return await invoke("set_credentials", { username, password, remember });
+}
+
+export async function invokeSubmitLoginCode(code) {
+    return await invoke("submit_login_code", { code });
+}
+
+export async function invokeCancelLoginCode() {
+    return await invoke("cancel_login_code");
+}
+
+export async function invokeStartSso() {
+    return await invoke("start_sso");
+}
+
+export async function invokeSetRememberPassword(remember) {
+    return await invoke("set_remember_password", { remember });
+}
+
+export async function invokeListAccounts() {
+    return await invoke("list_accounts");
+}
+
+export async function invokeSwitchAccount(accountid) {
+    return await invoke("switch_account", { accountid });
+}
+
+export async function invokeAddAccount() {
+    return await invoke("add_account");
+}
+
+export async function invokeLogout(everywhere) {
+    return await invoke("logout", { everywhere });
+}
+
+export async function invokeGetChildren() {
+    return await invoke("get_children");
+}
+
+export async function invokeSwitchChild(childid) {
+    return await invoke("switch_child", { childid });
 }
 
 export async function parseSingleClassInfo(classid) {
@@ -19,4 +59,68 @@ export async function parseSingleClassInfo(classid) {
 
 export async function invokeIsLoggedIn() {
     return await invoke("is_logged_in");
-}
\ No newline at end of file
+}
+
+export async function invokeGetGrades() {
+    return await invoke("get_grades");
+}
+
+export async function invokeGetClassInstructors(sectionid) {
+    return await invoke("get_class_instructors", { sectionid });
+}
+
+export async function invokeGetClassMembers(sectionid, search, page) {
+    return await invoke("get_class_members", { sectionid, search, page });
+}
+
+export async function invokeGetSubmission(materialid) {
+    return await invoke("get_submission", { materialid });
+}
+
+export async function invokeGetHomeFeed() {
+    return await invoke("get_home_feed");
+}
+
+export async function invokeGetSettings() {
+    return await invoke("get_settings");
+}
+
+export async function invokeSetSettings(settings) {
+    return await invoke("set_settings", { settings });
+}
+
+export async function pickSubmissionFiles() {
+    return await tauri.dialog.open({ multiple: true, title: "Choose files to submit" });
+}
+
+export async function invokeGetUpcoming() {
+    return await invoke("get_upcoming");
+}
+
+export async function invokeGetNotifications() {
+    return await invoke("get_notifications");
+}
+
+export async function invokeGetInbox() {
+    return await invoke("get_inbox");
+}
+
+export async function invokeGetMessageThread(threadid) {
+    return await invoke("get_message_thread", { threadid });
+}
+
+export async function invokeSendMessage(message) {
+    return await invoke("send_message", { message });
+}
+
+export async function invokeReplyMessage(threadid, body) {
+    return await invoke("reply_message", { threadid, body });
+}
+
+export async function invokeSubmitAssignment(materialid, upload, paths) {
+    return await invoke("submit_assignment", { materialid, upload, paths });
+}
+
+export async function listenEvents(callback) {
+    return await tauri.event.listen("bbs-event", (event) => callback(event.payload));
+}
@@ -0,0 +1,169 @@
+use bbs_shared::{accounts::{AccountSummary, ChildID}, FrontendData, PageState};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlSelectElement, MouseEvent};
+use yew::{function_component, html, Html, use_context, use_effect_with_deps, use_state_eq, UseReducerHandle};
+
+use crate::{add_account, build_classes, get_children, logout, set_remember_password, switch_account, switch_child};
+
+const WIDGET_BUTTON: &str = build_classes!(
+    "px-2 py-0.5",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "transition-colors duration-150",
+    "text-sm whitespace-nowrap",
+    "max-w-[12rem] overflow-hidden text-ellipsis",
+);
+
+const DROPDOWN: &str = build_classes!(
+    "absolute right-0 top-full mt-1",
+    "w-64",
+    "p-2",
+    "flex flex-col gap-1",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+    "shadow-lg",
+    "z-30",
+    "text-sm",
+);
+
+const ITEM_BASE: &str = build_classes!(
+    "px-3 py-1.5",
+    "rounded-md",
+    "text-left",
+    "overflow-hidden text-ellipsis whitespace-nowrap",
+    "transition-colors duration-150",
+);
+const ITEM_ACTIVE: &str = build_classes!(ITEM_BASE, "bg-slate-600 font-medium");
+const ITEM_INACTIVE: &str = build_classes!(ITEM_BASE, "hover:bg-slate-600");
+
+#[function_component(AccountSwitcher)]
+pub fn account_switcher() -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let expanded = use_state_eq(|| false);
+
+    let account_list = data.accounts.borrow().clone().unwrap_or_default();
+    let active_account = account_list
+        .accounts
+        .iter()
+        .find(|account| Some(&account.id) == account_list.active.as_ref());
+    let active = active_account
+        .map(|account| account.label().to_owned())
+        .unwrap_or_else(|| "Account".to_owned());
+    let remembered = active_account.map_or(false, |account| account.remember_password);
+
+    let toggle_expanded = expanded.clone();
+    let toggle = move |_: MouseEvent| toggle_expanded.set(!*toggle_expanded);
+
+    let account_html = |account: &AccountSummary| {
+        let is_active = Some(&account.id) == account_list.active.as_ref();
+        let switch_state = state.clone();
+        let switch_data = data.clone();
+        let switch_expanded = expanded.clone();
+        let id = account.id.clone();
+
+        let on_click = move |_: MouseEvent| {
+            switch_expanded.set(false);
+            if !is_active {
+                switch_account(id.clone(), switch_state.clone(), switch_data.clone());
+            }
+        };
+
+        html! {
+            <button class={if is_active { ITEM_ACTIVE } else { ITEM_INACTIVE }} onclick={on_click}>
+                {account.label()}
+            </button>
+        }
+    };
+
+    let add_state = state.clone();
+    let add_data = data.clone();
+    let add_expanded = expanded.clone();
+    let on_add = move |_: MouseEvent| {
+        add_expanded.set(false);
+        add_account(add_state.clone(), add_data.clone());
+    };
+
+    let remember_data = data.clone();
+    let on_remember = move |_: MouseEvent| set_remember_password(!remembered, remember_data.clone());
+
+    let logout_button = |label: &'static str, everywhere: bool| {
+        let logout_state = state.clone();
+        let logout_data = data.clone();
+        let logout_expanded = expanded.clone();
+        let on_click = move |_: MouseEvent| {
+            logout_expanded.set(false);
+            logout(everywhere, logout_state.clone(), logout_data.clone());
+        };
+
+        html! {
+            <button class={build_classes!(ITEM_INACTIVE, "text-red-400")} onclick={on_click}>{label}</button>
+        }
+    };
+
+    html! {
+        <div class="relative">
+            <button class={WIDGET_BUTTON} onclick={toggle}>{active}</button>
+            {if *expanded {
+                html! {
+                    <div class={DROPDOWN}>
+                        {account_list.accounts.iter().map(account_html).collect::<Html>()}
+                        <button class={build_classes!(ITEM_INACTIVE, "text-blue-400")} onclick={on_add}>{"Add account"}</button>
+                        <hr class="my-1 border-slate-500" />
+                        <button class={ITEM_INACTIVE} onclick={on_remember}>
+                            {if remembered { "✓ Remember password" } else { "Remember password" }}
+                        </button>
+                        {logout_button("Log out", false)}
+                        {logout_button("Log out everywhere", true)}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+/// Only shows up for parent accounts.
+#[function_component(ChildSwitcher)]
+pub fn child_switcher() -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    // a different account may or may not be a parent, so ask again whenever it changes
+    let active_account = data.accounts.borrow().as_ref().and_then(|accounts| accounts.active.clone());
+    let fetch_data = data.clone();
+    use_effect_with_deps(move |_| {
+        get_children(fetch_data);
+        || ()
+    }, active_account);
+
+    let parent_info = match data.children.borrow().clone() {
+        Some(parent_info) if parent_info.is_parent() => parent_info,
+        _ => return html! {},
+    };
+
+    let switch_data = data.clone();
+    let on_change = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value();
+        if let Ok(id) = value.parse() {
+            switch_child(ChildID(id), state.clone(), switch_data.clone());
+        }
+    };
+
+    html! {
+        <select class={WIDGET_BUTTON} onchange={on_change}>
+            {if parent_info.active.is_none() {
+                html! { <option value="" selected={true} disabled={true}>{"Choose a child"}</option> }
+            } else {
+                html! {}
+            }}
+            {parent_info.children
+                .iter()
+                .map(|child| html! {
+                    <option value={child.id.0.to_string()} selected={parent_info.active == Some(child.id)}>{&child.name}</option>
+                })
+                .collect::<Html>()}
+        </select>
+    }
+}
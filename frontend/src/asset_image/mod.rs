@@ -0,0 +1,30 @@
+use bbs_shared::FrontendData;
+use yew::{function_component, Properties, html, Classes, use_context, UseReducerHandle};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct AssetImageProps {
+    pub src: String,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// An image served over the backend's asset scheme. The backend answers 404 while it downloads something it
+/// hasn't cached yet, so once it reports the asset ready the url changes enough to make the webview retry.
+#[function_component(AssetImage)]
+pub fn asset_image(props: &AssetImageProps) -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    let ready = data
+        .ready_assets
+        .borrow()
+        .as_ref()
+        .map_or(false, |ready| ready.contains(&props.src));
+
+    let src = if ready {
+        format!("{}?ready", props.src)
+    } else {
+        props.src.clone()
+    };
+
+    html! { <img class={props.class.clone()} loading="lazy" {src}/> }
+}
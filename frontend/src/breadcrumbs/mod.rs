@@ -1,6 +1,6 @@
 use crate::build_classes;
 
-use yew::{function_component, Properties, html, Callback, use_state_eq, props};
+use yew::{function_component, Properties, html, Html, Callback, use_state_eq, props};
 
 #[derive(Debug, Clone, Properties, PartialEq)]
 pub struct BreadcrumbProps {
@@ -91,6 +91,8 @@ pub fn breadcrumb(props: &BreadcrumbProps) -> Html {
 pub struct BreadcrumbsProps {
     #[prop_or_default]
     pub children: Vec<BreadcrumbProps>,
+    #[prop_or_default]
+    pub widgets: Option<Html>,
 }
 
 #[function_component(Breadcrumbs)]
@@ -110,6 +112,11 @@ pub fn breadcrumbs(props: &BreadcrumbsProps) -> Html {
                     .map(|(idx, props)| html! { <Breadcrumb key={idx} ..props /> })
                     .collect::<Vec<_>>()
             }
+            {if let Some(widgets) = props.widgets.clone() {
+                html! { <div class="absolute right-3 top-0 h-full flex flex-row items-center gap-2">{widgets}</div> }
+            } else {
+                html! {}
+            }}
         </div>
     }
 }
@@ -1,9 +1,9 @@
-use bbs_shared::{data::{ClassEntry, SectionData, SectionDataGuts, OptMutComponent, Keyed, Assignment, AssignmentType}, PageState, StateUpdateAction, ClassID, MaterialID};
+use bbs_shared::{data::{ClassEntry, SectionData, SectionDataGuts, OptMutComponent, Keyed, Assignment, AssignmentType, Instructor, Submission, SubmissionState}, grades::GradeReport, state::ClassPageTab, PageState, StateUpdateAction, DataUpdateAction, FrontendData, ClassID, MaterialID, SectionID};
 use web_sys::MouseEvent;
-use yew::{function_component, Properties, html, Html, use_context, UseReducerHandle, Callback};
+use yew::{function_component, Properties, html, Html, use_context, use_effect_with_deps, UseReducerHandle, Callback};
 use web_sys::{window, console};
 
-use crate::build_classes;
+use crate::{build_classes, get_submission, submit_assignment, AssetImage, ClassPeople, GradeBreakdown};
 
 #[derive(Debug, Properties, PartialEq)]
 pub struct MaterialTypeProps {
@@ -31,6 +31,128 @@ pub fn material_type_display(props: &MaterialTypeProps) -> Html {
 }
 
 
+const CHIP_BASE: &str = build_classes!(
+    "inline-flex items-center",
+    "px-2 h-5",
+    "rounded-full",
+    "text-xs font-medium text-black",
+    "whitespace-nowrap",
+);
+
+fn chip_classes(state: SubmissionState) -> &'static str {
+    const NOT_SUBMITTED: &str = build_classes!(CHIP_BASE, "bg-slate-400");
+    const SUBMITTED: &str = build_classes!(CHIP_BASE, "bg-blue-400");
+    const LATE: &str = build_classes!(CHIP_BASE, "bg-amber-400");
+    const GRADED: &str = build_classes!(CHIP_BASE, "bg-green-400");
+    const EXCUSED: &str = build_classes!(CHIP_BASE, "bg-violet-400");
+
+    match state {
+        SubmissionState::NotSubmitted => NOT_SUBMITTED,
+        SubmissionState::Submitted => SUBMITTED,
+        SubmissionState::Late => LATE,
+        SubmissionState::Graded => GRADED,
+        SubmissionState::Excused => EXCUSED,
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct SubmissionChipProps {
+    pub state: SubmissionState,
+}
+
+#[function_component(SubmissionChip)]
+pub fn submission_chip(props: &SubmissionChipProps) -> Html {
+    html! {
+        <span class={chip_classes(props.state)}>{props.state.label()}</span>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct SubmissionDetailsProps {
+    pub material_id: MaterialID,
+    pub submission: Option<Submission>,
+}
+
+#[function_component(SubmissionDetails)]
+pub fn submission_details(props: &SubmissionDetailsProps) -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    let material_id = props.material_id;
+    let effect_data = data.clone();
+    use_effect_with_deps(move |_| {
+        get_submission(material_id, Callback::from(move |submission| effect_data.dispatch(DataUpdateAction::SetSubmission(material_id, submission))));
+        || ()
+    }, material_id);
+
+    let upload_progress = data
+        .upload_progress
+        .borrow()
+        .clone()
+        .filter(|progress| progress.material == material_id);
+
+    let submit_data = data.clone();
+    let on_submit = move |_: MouseEvent| submit_assignment(material_id, submit_data.clone());
+
+    let submission = match &props.submission {
+        Some(submission) => submission,
+        None => return html! {
+            <div class="text-center text-gray-500">{"Loading submission..."}</div>
+        },
+    };
+
+    html! {
+        <div class="flex flex-col items-center mt-3">
+            <div class="flex flex-row items-center gap-2">
+                <SubmissionChip state={submission.state} />
+                {if let Some(submitted_at) = &submission.submitted_at {
+                    html! { <span class="text-sm text-gray-400">{submitted_at}</span> }
+                } else {
+                    html! {}
+                }}
+            </div>
+            {if submission.files.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <ul class="mt-2 text-sm">
+                        {submission.files
+                            .iter()
+                            .map(|file| html! {
+                                <li>
+                                    <a class="underline text-blue-400" href={file.url.clone()} target="_blank">{&file.name}</a>
+                                    {if let Some(preview) = &file.preview {
+                                        html! { <AssetImage class="mt-1 max-w-xs max-h-40 rounded-md" src={preview.clone()}/> }
+                                    } else {
+                                        html! {}
+                                    }}
+                                </li>
+                            })
+                            .collect::<Html>()}
+                    </ul>
+                }
+            }}
+            {match (upload_progress, submission.state) {
+                (Some(progress), _) => html! {
+                    <div class="flex flex-col items-center mt-3 w-64">
+                        <span class="text-sm text-gray-400">
+                            {format!("Uploading file {} of {}", progress.file_index + 1, progress.file_count)}
+                        </span>
+                        <div class="w-full h-2 mt-1 rounded-full bg-slate-600 overflow-hidden">
+                            <div
+                                class="h-full bg-blue-400 transition-[width] duration-150"
+                                style={format!("width: {}%;", if progress.total > 0 { progress.sent * 100 / progress.total } else { 100 })}/>
+                        </div>
+                    </div>
+                },
+                (None, SubmissionState::Excused) => html! {},
+                (None, _) => html! {
+                    <button onclick={on_submit} class="mt-3 px-3 py-2 bg-violet-400 rounded-md text-black">{"Submit files…"}</button>
+                },
+            }}
+        </div>
+    }
+}
+
 #[derive(Debug, Properties, PartialEq)]
 pub struct ClassPageMaterialProps {
     pub assignment_data: Assignment,
@@ -69,8 +191,13 @@ pub fn class_page_material(props: &ClassPageMaterialProps) -> Html {
             onclick={move |_| callback.clone().emit(id)}>
             <MaterialTypeDisplay material_type={kind.clone()} />
             <div class={MAIN_BODY}>
-                <span class="flex flex-row text-2xl text-gray150 items-center">
+                <span class="flex flex-row text-2xl text-gray150 items-center gap-2">
                     {title}{"\u{a0}"}
+                    {if let Some(submission) = &props.assignment_data.submission {
+                        html! { <SubmissionChip state={submission.state} /> }
+                    } else {
+                        html! {}
+                    }}
                 </span>
                 <span class="text-sm text-gray-400 w-[50vw] overflow-hidden text-ellipsis block whitespace-nowrap">{body}{"\u{a0}"}</span>
             </div>
@@ -81,6 +208,35 @@ pub fn class_page_material(props: &ClassPageMaterialProps) -> Html {
 #[derive(Debug, Properties, PartialEq)]
 pub struct ClassPageProps {
     pub materials: Keyed<OptMutComponent<Vec<Assignment>>>,
+    pub grades: Option<GradeReport>,
+    pub instructors: Option<Vec<Instructor>>,
+    pub section: Option<SectionID>,
+    pub tab: ClassPageTab,
+}
+
+const TAB_BASE: &str = build_classes!(
+    "px-4 py-1.5",
+    "border-b-2",
+    "transition-colors duration-150",
+);
+const TAB_ACTIVE: &str = build_classes!(TAB_BASE, "border-violet-400 text-white");
+const TAB_INACTIVE: &str = build_classes!(TAB_BASE, "border-transparent text-gray-400 hover:text-white");
+
+fn instructor_html(instructor: &Instructor) -> Html {
+    html! {
+        <div class="flex flex-col px-3 py-1 rounded-lg bg-slate-700">
+            {if let Some(profile_url) = &instructor.profile_url {
+                html! { <a class="font-medium hover:underline" href={profile_url.clone()} target="_blank">{&instructor.name}</a> }
+            } else {
+                html! { <span class="font-medium">{&instructor.name}</span> }
+            }}
+            {if let Some(email) = &instructor.email {
+                html! { <a class="text-sm text-blue-400 hover:underline" href={format!("mailto:{}", email)}>{email}</a> }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
 }
 
 
@@ -129,7 +285,38 @@ pub fn class_page(props: &ClassPageProps) -> Html {
 
     html! {
         <div>
-            {material_html}
+            {if let Some(instructors) = props.instructors.as_ref().filter(|instructors| !instructors.is_empty()) {
+                html! {
+                    <div class="flex flex-row flex-wrap gap-2 mx-5 mt-3">
+                        {instructors.iter().map(instructor_html).collect::<Html>()}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+            <div class="flex flex-row mx-5 mt-3 border-b-[1px] border-slate-600">
+                {[(ClassPageTab::Materials, "Materials"), (ClassPageTab::Grades, "Grades"), (ClassPageTab::People, "People")]
+                    .into_iter()
+                    .map(|(tab, label)| {
+                        let state = state.clone();
+                        html! {
+                            <button
+                                class={if props.tab == tab { TAB_ACTIVE } else { TAB_INACTIVE }}
+                                onclick={move |_: MouseEvent| state.dispatch(StateUpdateAction::SetClassTab(tab))}>
+                                {label}
+                            </button>
+                        }
+                    })
+                    .collect::<Html>()}
+            </div>
+            {match (props.tab, props.section) {
+                (ClassPageTab::Materials, _) => material_html,
+                (ClassPageTab::Grades, _) => html! { <GradeBreakdown report={props.grades.clone()} /> },
+                (ClassPageTab::People, Some(section)) => html! { <ClassPeople section={section} /> },
+                (ClassPageTab::People, None) => html! {
+                    <div class="text-center text-gray-500">{"Members unavailable"}</div>
+                },
+            }}
         </div>
     }
 }
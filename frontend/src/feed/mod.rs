@@ -0,0 +1,129 @@
+use bbs_shared::{data::{FeedItem, OptMutComponent, Keyed}, PageState, FrontendData};
+use web_sys::MouseEvent;
+use yew::{function_component, Properties, html, Html, use_context, use_state_eq, UseReducerHandle, Callback};
+
+use crate::{build_classes, dispatch_open_section};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct HomeFeedItemProps {
+    pub item: FeedItem,
+    pub class_name: Option<String>,
+    pub open_callback: Callback<FeedItem>,
+}
+
+const FEED_ITEM: &str = build_classes!(
+    "flex flex-col",
+    "px-3 py-2",
+    "rounded-lg",
+    "bg-opacity-0 bg-zinc-500 hover:bg-opacity-25",
+    "transition-colors duration-200",
+    "cursor-pointer",
+);
+
+#[function_component(HomeFeedItem)]
+pub fn home_feed_item(props: &HomeFeedItemProps) -> Html {
+    let (kind, text) = match &props.item {
+        FeedItem::Update { author, body, .. } => ("Update", format!("{}: {}", author, body)),
+        FeedItem::NewMaterial { title, .. } => ("New material", title.clone()),
+        FeedItem::Graded { title, .. } => ("Graded", title.clone()),
+    };
+
+    let item = props.item.clone();
+    let callback = props.open_callback.clone();
+
+    html! {
+        <div
+            class={FEED_ITEM}
+            onclick={move |_| callback.emit(item.clone())}>
+            <span class="text-xs uppercase text-gray-400">
+                {kind}
+                {if let Some(class_name) = &props.class_name {
+                    html! {<>{" · "}{class_name}</>}
+                } else {
+                    html! {}
+                }}
+            </span>
+            <span class="text-sm overflow-hidden text-ellipsis whitespace-nowrap">{text}</span>
+            <span class="text-xs text-gray-500">{props.item.posted()}</span>
+        </div>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct HomeFeedProps {
+    pub items: Keyed<OptMutComponent<Vec<FeedItem>>>,
+}
+
+const PANEL_BASE: &str = build_classes!(
+    "shrink-0",
+    "border-l-2 border-solid border-slate-500",
+    "transition-[width] duration-300 ease-in-out",
+    "overflow-hidden",
+);
+const PANEL_EXPANDED: &str = build_classes!(PANEL_BASE, "w-80");
+const PANEL_COLLAPSED: &str = build_classes!(PANEL_BASE, "w-10");
+
+#[function_component(HomeFeed)]
+pub fn home_feed(props: &HomeFeedProps) -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let expanded = use_state_eq(|| true);
+
+    let toggle_expanded = expanded.clone();
+    let toggle = move |_: MouseEvent| toggle_expanded.set(!*toggle_expanded);
+
+    let classes_ref = data.classes.borrow();
+    let class_name_for = |item: &FeedItem| item
+        .section()
+        .and_then(|section| classes_ref
+            .as_ref()?
+            .iter()
+            .find(|entry| entry.section_nid == section)
+            .map(|entry| entry.display_name().to_owned())
+        );
+
+    let open_state = state.clone();
+    let open_data = data.clone();
+    let open_callback = Callback::from(move |item: FeedItem| {
+        if let Some(section) = item.section() {
+            dispatch_open_section(section, item.material(), open_state.clone(), open_data.clone());
+        }
+    });
+
+    let items_ref = props.items.borrow();
+    let items_html = match items_ref.as_ref() {
+        Some(items) if items.is_empty() => html! {
+            <div class="text-center text-gray-500">{"Nothing new"}</div>
+        },
+        Some(items) => items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| html! {
+                <HomeFeedItem
+                    key={idx}
+                    item={item.clone()}
+                    class_name={class_name_for(item)}
+                    open_callback={open_callback.clone()}/>
+            })
+            .collect::<Html>(),
+        None => html! {
+            <div class="text-center text-gray-500">{"Loading..."}</div>
+        },
+    };
+
+    html! {
+        <div class={if *expanded { PANEL_EXPANDED } else { PANEL_COLLAPSED }}>
+            <div
+                class="flex flex-row items-center justify-between h-10 px-3 cursor-pointer"
+                onclick={toggle}>
+                {if *expanded { html! {<span class="font-medium whitespace-nowrap">{"Recent activity"}</span>} } else { html! {} }}
+                <span>{if *expanded { "»" } else { "«" }}</span>
+            </div>
+            {if *expanded {
+                html! { <div class="flex flex-col px-1">{items_html}</div> }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,281 @@
+use bbs_shared::{gpa::{compute_gpa, CourseLevel, GpaScale, GpaScaleKind, LetterCutoff}, data::ClassEntry, settings::Settings, FrontendData};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, MouseEvent};
+use yew::{function_component, html, Html, use_context, use_state_eq, UseReducerHandle};
+
+use crate::{build_classes, update_settings};
+
+const WIDGET_BUTTON: &str = build_classes!(
+    "px-2 py-0.5",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "transition-colors duration-150",
+    "text-sm whitespace-nowrap",
+);
+
+const DROPDOWN: &str = build_classes!(
+    "absolute right-0 top-full mt-1",
+    "w-96 max-h-[70vh] overflow-y-scroll",
+    "p-3",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+    "shadow-lg",
+    "z-30",
+);
+
+const SCALE_BUTTON_BASE: &str = build_classes!("flex-1 px-2 py-1 rounded-md transition-colors duration-150");
+const SCALE_BUTTON_ACTIVE: &str = build_classes!(SCALE_BUTTON_BASE, "bg-blue-400 text-black");
+const SCALE_BUTTON_INACTIVE: &str = build_classes!(SCALE_BUTTON_BASE, "bg-slate-600 hover:bg-slate-500");
+
+const CREDITS_INPUT: &str = "w-14 h-6 rounded-md bg-slate-600 border-[1px] border-slate-500 px-1 text-sm";
+const LEVEL_SELECT: &str = "h-6 rounded-md bg-slate-600 border-[1px] border-slate-500 text-sm";
+const SECTION_TOGGLE: &str = "text-xs text-gray-400 hover:text-gray-200 hover:underline";
+
+/// Parses a number input once the user is done with it, so a half-typed value never lands in settings.
+fn number_value(event: &Event) -> Option<f64> {
+    event
+        .target()?
+        .dyn_into::<HtmlInputElement>()
+        .ok()?
+        .value()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value >= 0.0)
+}
+
+fn bump_inputs(honors_bump: f64, ap_bump: f64, data: &UseReducerHandle<FrontendData>) -> Html {
+    let set_bump = |data: UseReducerHandle<FrontendData>, is_ap: bool| move |event: Event| {
+        if let Some(bump) = number_value(&event) {
+            update_settings(data.clone(), |settings| {
+                if let GpaScaleKind::Weighted { honors_bump, ap_bump } = &mut settings.gpa.scale.kind {
+                    *if is_ap { ap_bump } else { honors_bump } = bump;
+                }
+            });
+        }
+    };
+
+    html! {
+        <div class="flex flex-row items-center gap-2 mb-2 text-sm">
+            <span class="flex-1">{"Honors bump"}</span>
+            <input
+                class={CREDITS_INPUT}
+                type="number"
+                step="0.25"
+                min="0"
+                value={honors_bump.to_string()}
+                onchange={set_bump(data.clone(), false)} />
+            <span class="flex-1 text-right">{"AP bump"}</span>
+            <input
+                class={CREDITS_INPUT}
+                type="number"
+                step="0.25"
+                min="0"
+                value={ap_bump.to_string()}
+                onchange={set_bump(data.clone(), true)} />
+        </div>
+    }
+}
+
+fn cutoff_row(index: usize, cutoff: &LetterCutoff, data: &UseReducerHandle<FrontendData>) -> Html {
+    let percent_data = data.clone();
+    let on_percent = move |event: Event| {
+        if let Some(min_percent) = number_value(&event).filter(|percent| *percent <= 100.0) {
+            update_settings(percent_data.clone(), |settings| {
+                if let Some(cutoff) = settings.gpa.scale.cutoffs.get_mut(index) {
+                    cutoff.min_percent = min_percent;
+                }
+                settings.gpa.scale.sort_cutoffs();
+            });
+        }
+    };
+
+    let points_data = data.clone();
+    let on_points = move |event: Event| {
+        if let Some(points) = number_value(&event) {
+            update_settings(points_data.clone(), |settings| {
+                if let Some(cutoff) = settings.gpa.scale.cutoffs.get_mut(index) {
+                    cutoff.points = points;
+                }
+            });
+        }
+    };
+
+    html! {
+        <div class="flex flex-row items-center gap-2 py-0.5 text-sm">
+            <span class="flex-1">{&cutoff.letter}</span>
+            <input
+                class={CREDITS_INPUT}
+                type="number"
+                step="1"
+                min="0"
+                max="100"
+                value={cutoff.min_percent.to_string()}
+                onchange={on_percent} />
+            <input
+                class={CREDITS_INPUT}
+                type="number"
+                step="0.1"
+                min="0"
+                value={cutoff.points.to_string()}
+                onchange={on_points} />
+        </div>
+    }
+}
+
+fn class_row(entry: &ClassEntry, settings: &Settings, data: &UseReducerHandle<FrontendData>) -> Html {
+    let section = entry.section_nid;
+    let class_settings = settings.gpa.class_settings(section);
+    let affects = settings.gpa.affects_gpa(entry);
+
+    let affects_data = data.clone();
+    let on_affects = move |event: Event| {
+        let checked = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().checked();
+        update_settings(affects_data.clone(), |settings| settings.gpa.classes.entry(section).or_default().affects_gpa = Some(checked));
+    };
+
+    let credits_data = data.clone();
+    let on_credits = move |event: Event| {
+        if let Some(credits) = number_value(&event) {
+            update_settings(credits_data.clone(), |settings| settings.gpa.classes.entry(section).or_default().credits = credits);
+        }
+    };
+
+    let level_data = data.clone();
+    let on_level = move |event: Event| {
+        let level = match event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value().as_str() {
+            "honors" => CourseLevel::Honors,
+            "ap" => CourseLevel::AdvancedPlacement,
+            _ => CourseLevel::Regular,
+        };
+        update_settings(level_data.clone(), |settings| settings.gpa.classes.entry(section).or_default().level = level);
+    };
+
+    html! {
+        <div class="flex flex-row items-center gap-2 py-1">
+            <input type="checkbox" checked={affects} onchange={on_affects} />
+            <span class="flex-1 overflow-hidden text-ellipsis whitespace-nowrap text-sm">{entry.display_name()}</span>
+            <input
+                class={CREDITS_INPUT}
+                type="number"
+                step="0.25"
+                min="0"
+                value={class_settings.credits.to_string()}
+                onchange={on_credits} />
+            <select class={LEVEL_SELECT} onchange={on_level}>
+                <option value="regular" selected={class_settings.level == CourseLevel::Regular}>{"Reg."}</option>
+                <option value="honors" selected={class_settings.level == CourseLevel::Honors}>{"Hon."}</option>
+                <option value="ap" selected={class_settings.level == CourseLevel::AdvancedPlacement}>{"AP"}</option>
+            </select>
+        </div>
+    }
+}
+
+#[function_component(GpaWidget)]
+pub fn gpa_widget() -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let expanded = use_state_eq(|| false);
+    let editing_cutoffs = use_state_eq(|| false);
+
+    let settings = data.settings.borrow().clone().unwrap_or_default();
+    let classes_ref = data.classes.borrow();
+    let grades_ref = data.grades.borrow();
+
+    let gpa = match (classes_ref.as_ref(), grades_ref.as_ref()) {
+        (Some(classes), Some(grades)) => compute_gpa(classes, grades, &settings.gpa),
+        _ => None,
+    };
+
+    let toggle_expanded = expanded.clone();
+    let toggle = move |_: MouseEvent| toggle_expanded.set(!*toggle_expanded);
+
+    let is_weighted = matches!(settings.gpa.scale.kind, GpaScaleKind::Weighted { .. });
+
+    // Switching scales keeps any cutoffs the user already edited.
+    let unweighted_data = data.clone();
+    let set_unweighted = move |_: MouseEvent| update_settings(unweighted_data.clone(), |settings| settings.gpa.scale.kind = GpaScaleKind::Unweighted);
+    let weighted_data = data.clone();
+    let set_weighted = move |_: MouseEvent| update_settings(weighted_data.clone(), |settings| {
+        if !matches!(settings.gpa.scale.kind, GpaScaleKind::Weighted { .. }) {
+            settings.gpa.scale.kind = GpaScale::weighted_4().kind;
+        }
+    });
+
+    let toggle_cutoffs = editing_cutoffs.clone();
+    let toggle_editing = move |_: MouseEvent| toggle_cutoffs.set(!*toggle_cutoffs);
+    let reset_data = data.clone();
+    let reset_cutoffs = move |_: MouseEvent| update_settings(reset_data.clone(), |settings| settings.gpa.scale.cutoffs = GpaScale::default().cutoffs);
+
+    html! {
+        <div class="relative">
+            <button class={WIDGET_BUTTON} onclick={toggle}>
+                {"GPA "}
+                <span class="font-medium">{gpa.map(|gpa| format!("{:.2}", gpa)).unwrap_or_else(|| "—".to_string())}</span>
+            </button>
+            {if *expanded {
+                html! {
+                    <div class={DROPDOWN}>
+                        <div class="flex flex-row gap-2 mb-2">
+                            <button
+                                class={if is_weighted { SCALE_BUTTON_INACTIVE } else { SCALE_BUTTON_ACTIVE }}
+                                onclick={set_unweighted}>
+                                {"Unweighted"}
+                            </button>
+                            <button
+                                class={if is_weighted { SCALE_BUTTON_ACTIVE } else { SCALE_BUTTON_INACTIVE }}
+                                onclick={set_weighted}>
+                                {"Weighted"}
+                            </button>
+                        </div>
+                        {match settings.gpa.scale.kind {
+                            GpaScaleKind::Weighted { honors_bump, ap_bump } => bump_inputs(honors_bump, ap_bump, &data),
+                            GpaScaleKind::Unweighted => html! {},
+                        }}
+                        <div class="flex flex-row gap-3 mb-2">
+                            <button class={SECTION_TOGGLE} onclick={toggle_editing}>
+                                {if *editing_cutoffs { "Hide letter cutoffs" } else { "Edit letter cutoffs" }}
+                            </button>
+                            {if *editing_cutoffs {
+                                html! { <button class={SECTION_TOGGLE} onclick={reset_cutoffs}>{"Reset"}</button> }
+                            } else {
+                                html! {}
+                            }}
+                        </div>
+                        {if *editing_cutoffs {
+                            html! {
+                                <div class="mb-2">
+                                    <div class="flex flex-row text-xs text-gray-400 gap-2">
+                                        <span class="flex-1">{"Letter"}</span>
+                                        <span class="w-14">{"Min %"}</span>
+                                        <span class="w-14">{"Points"}</span>
+                                    </div>
+                                    {settings.gpa.scale.cutoffs
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(index, cutoff)| cutoff_row(index, cutoff, &data))
+                                        .collect::<Html>()}
+                                </div>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        <div class="flex flex-row text-xs text-gray-400 gap-2">
+                            <span class="flex-1">{"Counts · Class"}</span>
+                            <span class="w-14">{"Credits"}</span>
+                            <span class="w-12">{"Level"}</span>
+                        </div>
+                        {classes_ref
+                            .as_ref()
+                            .map(|classes| classes
+                                .iter()
+                                .map(|entry| class_row(entry, &settings, &data))
+                                .collect::<Html>()
+                            )
+                            .unwrap_or_else(|| html! {})}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
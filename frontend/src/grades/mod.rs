@@ -0,0 +1,262 @@
+use bbs_shared::{grades::{GradeReport, GradingPeriod, GradeCategory, GradedItem}, gpa::{GpaScale, LetterCutoff}, what_if::{WhatIf, ItemKey}, FrontendData};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, MouseEvent};
+use yew::{function_component, Properties, html, Html, use_context, use_state, UseReducerHandle};
+
+use crate::build_classes;
+
+const ROW_BASE: &str = build_classes!(
+    "flex flex-row items-center justify-between",
+    "px-3",
+);
+const PERIOD_ROW: &str = build_classes!(ROW_BASE, "py-2 text-xl font-medium border-b-2 border-slate-500");
+const CATEGORY_ROW: &str = build_classes!(ROW_BASE, "py-1.5 pl-6 text-lg text-gray150");
+const ITEM_ROW: &str = build_classes!(ROW_BASE, "py-1 pl-10 text-sm text-gray-300");
+
+fn format_score(item: &GradedItem) -> String {
+    match (item.score, item.max) {
+        (Some(score), Some(max)) => format!("{} / {}", score, max),
+        (Some(score), None) => score.to_string(),
+        (None, Some(max)) => format!("— / {}", max),
+        (None, None) => "—".to_string(),
+    }
+}
+
+fn graded_item_html(item: &GradedItem) -> Html {
+    html! {
+        <div class="flex flex-col">
+            <div class={ITEM_ROW}>
+                <span class="overflow-hidden text-ellipsis whitespace-nowrap">{&item.title}</span>
+                <span class="shrink-0 ml-3">{format_score(item)}</span>
+            </div>
+            {if let Some(comment) = &item.comment {
+                html! { <span class="pl-12 pr-3 text-xs italic text-gray-400">{comment}</span> }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+fn category_html(category: &GradeCategory) -> Html {
+    html! {
+        <div class="flex flex-col">
+            <div class={CATEGORY_ROW}>
+                <span>
+                    {&category.name}
+                    {if let Some(weight) = category.weight {
+                        html! { <span class="ml-2 text-sm text-gray-400">{format!("({}%)", weight)}</span> }
+                    } else {
+                        html! {}
+                    }}
+                </span>
+                <span>{category.grade.as_ref().map(ToString::to_string).unwrap_or_default()}</span>
+            </div>
+            {category.items.iter().map(graded_item_html).collect::<Html>()}
+        </div>
+    }
+}
+
+fn period_html(period: &GradingPeriod) -> Html {
+    html! {
+        <div class="flex flex-col mb-3">
+            <div class={PERIOD_ROW}>
+                <span>{&period.name}</span>
+                <span>{period.grade.as_ref().map(ToString::to_string).unwrap_or_default()}</span>
+            </div>
+            {period.categories.iter().map(category_html).collect::<Html>()}
+        </div>
+    }
+}
+
+const WHAT_IF_INPUT: &str = "w-16 h-6 rounded-md bg-slate-600 border-[1px] border-slate-500 px-1 text-sm";
+const WHAT_IF_SELECT: &str = "h-7 rounded-md bg-slate-600 border-[1px] border-slate-500 text-sm";
+
+fn letter_for(cutoffs: &[LetterCutoff], percent: f64) -> Option<&str> {
+    cutoffs
+        .iter()
+        .find(|cutoff| percent >= cutoff.min_percent)
+        .map(|cutoff| cutoff.letter.as_str())
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct WhatIfPanelProps {
+    pub period: GradingPeriod,
+    pub cutoffs: Vec<LetterCutoff>,
+}
+
+#[function_component(WhatIfPanel)]
+pub fn what_if_panel(props: &WhatIfPanelProps) -> Html {
+    let what_if = use_state(WhatIf::default);
+    let target = use_state(|| None::<ItemKey>);
+    let goal = use_state(|| props.cutoffs.iter().position(|cutoff| cutoff.letter == "A").unwrap_or(0));
+
+    let period = &props.period;
+
+    let projected = what_if.period_percent(period);
+
+    let item_rows = period.categories
+        .iter()
+        .enumerate()
+        .flat_map(|(category_idx, category)| category.items
+            .iter()
+            .enumerate()
+            .map(move |(item_idx, item)| ((category_idx, item_idx), item))
+        )
+        .map(|(key, item)| {
+            let what_if = what_if.clone();
+            let on_input = move |event: yew::events::InputEvent| {
+                let value = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+                let mut updated = (*what_if).clone();
+                match value.trim().parse::<f64>() {
+                    Ok(score) => updated.scores.insert(key, score),
+                    Err(_) => updated.scores.remove(&key),
+                };
+                what_if.set(updated);
+            };
+
+            html! {
+                <div class="flex flex-row items-center justify-between py-0.5 pl-4 text-sm">
+                    <span class="overflow-hidden text-ellipsis whitespace-nowrap">{&item.title}</span>
+                    <span class="shrink-0 ml-3">
+                        <input
+                            class={WHAT_IF_INPUT}
+                            type="number"
+                            placeholder={item.score.map(|score| score.to_string()).unwrap_or_else(|| "—".to_string())}
+                            oninput={on_input} />
+                        {" / "}{item.max.map(|max| max.to_string()).unwrap_or_else(|| "—".to_string())}
+                    </span>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let target_state = target.clone();
+    let on_target = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value();
+        target_state.set(
+            value
+                .split_once('-')
+                .and_then(|(category, item)| Some((category.parse().ok()?, item.parse().ok()?)))
+        );
+    };
+
+    let goal_state = goal.clone();
+    let on_goal = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value();
+        if let Ok(idx) = value.parse() {
+            goal_state.set(idx);
+        }
+    };
+
+    let reset_what_if = what_if.clone();
+    let on_reset = move |_: MouseEvent| reset_what_if.set(WhatIf::default());
+
+    let selected_target = *target;
+    let target_options = period.categories
+        .iter()
+        .enumerate()
+        .flat_map(|(category_idx, category)| category.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.max.is_some())
+            .map(move |(item_idx, item)| html! {
+                <option
+                    value={format!("{}-{}", category_idx, item_idx)}
+                    selected={selected_target == Some((category_idx, item_idx))}>
+                    {&item.title}
+                </option>
+            })
+        )
+        .collect::<Html>();
+
+    let requirement = selected_target.and_then(|target_key| {
+        let cutoff = props.cutoffs.get(*goal)?;
+        let max = period.categories.get(target_key.0)?.items.get(target_key.1)?.max?;
+        let needed = what_if.required_score(period, target_key, cutoff.min_percent)?;
+        Some((needed, max, cutoff.letter.clone()))
+    });
+
+    html! {
+        <div class="mt-3 p-3 rounded-lg bg-slate-700">
+            <div class="flex flex-row items-center justify-between mb-2">
+                <span class="text-lg font-medium">{"What if?"}</span>
+                <span>
+                    {"Projected: "}
+                    <span class="font-medium">
+                        {match projected {
+                            Some(percent) => format!("{} ({:.1}%)", letter_for(&props.cutoffs, percent).unwrap_or("—"), percent),
+                            None => "—".to_string(),
+                        }}
+                    </span>
+                    <button class="ml-3 px-2 rounded-md bg-slate-600 hover:bg-slate-500 text-sm" onclick={on_reset}>{"Reset"}</button>
+                </span>
+            </div>
+            {item_rows}
+            <div class="flex flex-row items-center gap-2 mt-3 text-sm">
+                <span>{"To get"}</span>
+                <select class={WHAT_IF_SELECT} onchange={on_goal}>
+                    {props.cutoffs
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, cutoff)| html! {
+                            <option value={idx.to_string()} selected={*goal == idx}>{&cutoff.letter}</option>
+                        })
+                        .collect::<Html>()}
+                </select>
+                <span>{"I need on"}</span>
+                <select class={WHAT_IF_SELECT} onchange={on_target}>
+                    <option value="" selected={selected_target.is_none()}>{"Choose an assignment"}</option>
+                    {target_options}
+                </select>
+            </div>
+            {match requirement {
+                Some((needed, max, _)) if needed <= 0.0 => html! {
+                    <div class="mt-1 text-sm text-green-400">{format!("Already secured, even with 0 / {}.", max)}</div>
+                },
+                Some((needed, max, letter)) if needed > max => html! {
+                    <div class="mt-1 text-sm text-red-400">{format!("{} is out of reach: you would need {:.1} / {}.", letter, needed, max)}</div>
+                },
+                Some((needed, max, _)) => html! {
+                    <div class="mt-1 text-sm">{format!("You need at least {:.1} / {} ({:.1}%).", needed, max, needed / max * 100.0)}</div>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct GradeBreakdownProps {
+    pub report: Option<GradeReport>,
+}
+
+#[function_component(GradeBreakdown)]
+pub fn grade_breakdown(props: &GradeBreakdownProps) -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let cutoffs = data
+        .settings
+        .borrow()
+        .as_ref()
+        .map(|settings| settings.gpa.scale.cutoffs.clone())
+        .unwrap_or_else(|| GpaScale::default().cutoffs);
+
+    match &props.report {
+        Some(report) if !report.periods.is_empty() => html! {
+            <div class="mx-5 my-3">
+                {report.periods.iter().map(period_html).collect::<Html>()}
+                {if let Some(period) = report.current_period() {
+                    html! { <WhatIfPanel period={period.clone()} cutoffs={cutoffs} /> }
+                } else {
+                    html! {}
+                }}
+            </div>
+        },
+        Some(_) => html! {
+            <div class="text-center text-gray-500">{"No grades posted"}</div>
+        },
+        None => html! {
+            <div class="text-center text-gray-500">{"Loading grades..."}</div>
+        },
+    }
+}
@@ -0,0 +1,342 @@
+use bbs_shared::{messages::{unread_count, NewMessage, ThreadID, ThreadSummary, UserID}, FrontendData, PageState, StateUpdateAction};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, MouseEvent};
+use yew::{function_component, Properties, html, Html, use_context, use_effect_with_deps, use_state, use_state_eq, UseReducerHandle, Callback};
+
+use crate::{build_classes, get_inbox, get_message_thread, reply_message, send_message};
+
+const WIDGET_BUTTON: &str = build_classes!(
+    "relative",
+    "px-2 py-0.5",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "transition-colors duration-150",
+    "text-sm whitespace-nowrap",
+);
+
+const UNREAD_BADGE: &str = build_classes!(
+    "ml-1 px-1.5",
+    "rounded-full",
+    "bg-violet-400 text-black",
+    "text-xs font-medium",
+);
+
+const THREAD_BASE: &str = build_classes!(
+    "flex flex-col",
+    "px-3 py-2",
+    "rounded-lg",
+    "cursor-pointer",
+    "transition-colors duration-200",
+);
+const THREAD_ACTIVE: &str = build_classes!(THREAD_BASE, "bg-slate-600");
+const THREAD_INACTIVE: &str = build_classes!(THREAD_BASE, "bg-opacity-0 bg-zinc-500 hover:bg-opacity-25");
+
+const TEXT_INPUT: &str = build_classes!(
+    "w-full px-3 py-1.5",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+);
+
+const SEND_BUTTON: &str = build_classes!(
+    "self-end mt-2 px-3 py-1.5",
+    "rounded-md",
+    "bg-violet-400 text-black",
+    "disabled:opacity-50",
+);
+
+#[function_component(InboxWidget)]
+pub fn inbox_widget() -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    let mount_data = data.clone();
+    use_effect_with_deps(move |_| {
+        if mount_data.inbox.borrow().is_none() {
+            get_inbox(mount_data.clone());
+        }
+        || ()
+    }, ());
+
+    let unread = data
+        .inbox
+        .borrow()
+        .as_ref()
+        .map(|threads| unread_count(threads))
+        .unwrap_or(0);
+
+    html! {
+        <button class={WIDGET_BUTTON} onclick={move |_: MouseEvent| state.dispatch(StateUpdateAction::ToInbox)}>
+            {"Inbox"}
+            {if unread > 0 {
+                html! { <span class={UNREAD_BADGE}>{unread}</span> }
+            } else {
+                html! {}
+            }}
+        </button>
+    }
+}
+
+fn thread_summary_html(summary: &ThreadSummary, selected: bool, state: &UseReducerHandle<PageState>) -> Html {
+    let state = state.clone();
+    let id = summary.id;
+
+    html! {
+        <div
+            class={if selected { THREAD_ACTIVE } else { THREAD_INACTIVE }}
+            onclick={move |_: MouseEvent| state.dispatch(StateUpdateAction::OpenThread(id))}>
+            <span class="flex flex-row items-center justify-between">
+                <span class={if summary.unread { "font-semibold" } else { "" }}>{&summary.subject}</span>
+                <span class="text-xs text-gray-400 shrink-0 ml-2">{&summary.updated}</span>
+            </span>
+            <span class="text-sm text-gray-400 overflow-hidden text-ellipsis whitespace-nowrap">
+                {summary.participants.join(", ")}
+            </span>
+            <span class="text-sm text-gray-300 overflow-hidden text-ellipsis whitespace-nowrap">{&summary.snippet}</span>
+        </div>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct ThreadViewProps {
+    pub thread: ThreadID,
+}
+
+#[function_component(ThreadView)]
+pub fn thread_view(props: &ThreadViewProps) -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let reply = use_state_eq(String::new);
+    let sending = use_state_eq(|| false);
+    let error = use_state_eq(|| None::<String>);
+
+    let load_data = data.clone();
+    use_effect_with_deps(move |thread| {
+        get_message_thread(*thread, load_data);
+        || ()
+    }, props.thread);
+
+    let thread = data
+        .message_thread
+        .borrow()
+        .clone()
+        .filter(|thread| thread.id == props.thread);
+
+    let thread = match thread {
+        Some(thread) => thread,
+        None => return html! { <h1 class="ml-7">{"Loading..."}</h1> },
+    };
+
+    let reply_state = reply.clone();
+    let on_reply_input = move |event: yew::events::InputEvent| {
+        reply_state.set(event.target().unwrap().dyn_into::<HtmlTextAreaElement>().unwrap().value());
+    };
+
+    let send_data = data.clone();
+    let send_reply = reply.clone();
+    let send_sending = sending.clone();
+    let send_error = error.clone();
+    let thread_id = thread.id;
+    let on_send = move |_: MouseEvent| {
+        let body = (*send_reply).clone();
+        if body.trim().is_empty() {
+            return;
+        }
+        send_sending.set(true);
+        send_error.set(None);
+
+        let done_reply = send_reply.clone();
+        let done_sending = send_sending.clone();
+        let done_error = send_error.clone();
+        reply_message(thread_id, body, send_data.clone(), Callback::from(move |error: Option<String>| {
+            done_sending.set(false);
+            if error.is_none() {
+                done_reply.set(String::new());
+            }
+            done_error.set(error);
+        }));
+    };
+
+    html! {
+        <div class="flex flex-col">
+            <span class="text-2xl font-medium">{&thread.subject}</span>
+            <span class="text-sm text-gray-400 mb-3">{thread.participants.join(", ")}</span>
+            {thread.messages
+                .iter()
+                .map(|message| html! {
+                    <div class="flex flex-col mb-2 p-3 rounded-lg bg-slate-700">
+                        <span class="flex flex-row justify-between text-sm">
+                            <span class="font-medium">{&message.author}</span>
+                            <span class="text-gray-400">{&message.sent}</span>
+                        </span>
+                        <p class="mt-1 whitespace-pre-wrap">{&message.body}</p>
+                    </div>
+                })
+                .collect::<Html>()}
+            <textarea
+                class={build_classes!(TEXT_INPUT, "mt-2 h-24")}
+                placeholder="Write a reply"
+                value={(*reply).clone()}
+                oninput={on_reply_input} />
+            {if let Some(error) = &*error {
+                html! { <span class="mt-1 text-sm text-red-400">{error}</span> }
+            } else {
+                html! {}
+            }}
+            <button class={SEND_BUTTON} disabled={*sending} onclick={on_send}>
+                {if *sending { "Sending…" } else { "Reply" }}
+            </button>
+        </div>
+    }
+}
+
+#[function_component(ComposeMessage)]
+pub fn compose_message() -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let recipients = use_state(Vec::<UserID>::new);
+    let subject = use_state_eq(String::new);
+    let body = use_state_eq(String::new);
+    let sending = use_state_eq(|| false);
+    let error = use_state_eq(|| None::<String>);
+
+    // teachers are who students message most, and their ids come for free with the class listing
+    let mut contacts: Vec<(UserID, String)> = data
+        .classes
+        .borrow()
+        .iter()
+        .flatten()
+        .flat_map(|entry| entry.instructors.iter().flatten())
+        .filter_map(|instructor| Some((UserID::from_profile_url(instructor.profile_url.as_ref()?)?, instructor.name.clone())))
+        .collect();
+    contacts.sort_by(|a, b| a.1.cmp(&b.1));
+    contacts.dedup_by_key(|(id, _)| *id);
+
+    let contact_rows = contacts
+        .into_iter()
+        .map(|(id, name)| {
+            let recipients = recipients.clone();
+            let on_toggle = move |event: yew::events::Event| {
+                let checked = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().checked();
+                let mut updated = (*recipients).clone();
+                updated.retain(|recipient| *recipient != id);
+                if checked {
+                    updated.push(id);
+                }
+                recipients.set(updated);
+            };
+            html! {
+                <label class="flex flex-row items-center gap-2 text-sm">
+                    <input type="checkbox" onchange={on_toggle} />
+                    {name}
+                </label>
+            }
+        })
+        .collect::<Html>();
+
+    let subject_state = subject.clone();
+    let on_subject = move |event: yew::events::InputEvent| {
+        subject_state.set(event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value());
+    };
+    let body_state = body.clone();
+    let on_body = move |event: yew::events::InputEvent| {
+        body_state.set(event.target().unwrap().dyn_into::<HtmlTextAreaElement>().unwrap().value());
+    };
+
+    let can_send = !recipients.is_empty() && !body.trim().is_empty() && !*sending;
+
+    let send_state = state.clone();
+    let send_data = data.clone();
+    let send_sending = sending.clone();
+    let send_error = error.clone();
+    let message = NewMessage {
+        recipients: (*recipients).clone(),
+        subject: (*subject).clone(),
+        body: (*body).clone(),
+    };
+    let on_send = move |_: MouseEvent| {
+        send_sending.set(true);
+        send_error.set(None);
+
+        let done_state = send_state.clone();
+        let done_data = send_data.clone();
+        let done_sending = send_sending.clone();
+        let done_error = send_error.clone();
+        send_message(message.clone(), Callback::from(move |error: Option<String>| {
+            done_sending.set(false);
+            match error {
+                Some(error) => done_error.set(Some(error)),
+                None => {
+                    get_inbox(done_data.clone());
+                    done_state.dispatch(StateUpdateAction::ToInbox);
+                },
+            }
+        }));
+    };
+
+    html! {
+        <div class="flex flex-col gap-2">
+            <span class="text-2xl font-medium">{"New message"}</span>
+            <div class="flex flex-col gap-1 max-h-40 overflow-y-scroll">{contact_rows}</div>
+            <input class={TEXT_INPUT} placeholder="Subject" value={(*subject).clone()} oninput={on_subject} />
+            <textarea class={build_classes!(TEXT_INPUT, "h-40")} placeholder="Message" value={(*body).clone()} oninput={on_body} />
+            {if let Some(error) = &*error {
+                html! { <span class="text-sm text-red-400">{error}</span> }
+            } else {
+                html! {}
+            }}
+            <button class={SEND_BUTTON} disabled={!can_send} onclick={on_send}>
+                {if *sending { "Sending…" } else { "Send" }}
+            </button>
+        </div>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct InboxPageProps {
+    pub thread: Option<ThreadID>,
+    pub composing: bool,
+}
+
+#[function_component(InboxPage)]
+pub fn inbox_page(props: &InboxPageProps) -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    let mount_data = data.clone();
+    use_effect_with_deps(move |_| {
+        get_inbox(mount_data);
+        || ()
+    }, ());
+
+    let compose_state = state.clone();
+
+    html! {
+        <div class="flex flex-row gap-5 mx-5 my-3">
+            <div class="flex flex-col w-96 shrink-0 gap-1">
+                <button
+                    class={build_classes!(SEND_BUTTON, "self-stretch mt-0 mb-2")}
+                    onclick={move |_: MouseEvent| compose_state.dispatch(StateUpdateAction::ComposeMessage)}>
+                    {"New message"}
+                </button>
+                {match data.inbox.borrow().as_ref() {
+                    Some(threads) if threads.is_empty() => html! {
+                        <div class="text-center text-gray-500">{"No messages"}</div>
+                    },
+                    Some(threads) => threads
+                        .iter()
+                        .map(|summary| thread_summary_html(summary, props.thread == Some(summary.id), &state))
+                        .collect::<Html>(),
+                    None => html! { <div class="text-center text-gray-500">{"Loading messages..."}</div> },
+                }}
+            </div>
+            <div class="flex-1">
+                {match (props.thread, props.composing) {
+                    (_, true) => html! { <ComposeMessage /> },
+                    (Some(thread), false) => html! { <ThreadView thread={thread} /> },
+                    (None, false) => html! {
+                        <div class="text-center text-gray-500">{"Select a conversation"}</div>
+                    },
+                }}
+            </div>
+        </div>
+    }
+}
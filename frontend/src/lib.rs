@@ -2,15 +2,36 @@ mod login;
 mod breadcrumbs;
 mod main_page;
 mod class_page;
+mod feed;
+mod grades;
+mod gpa;
+mod people;
+mod inbox;
+mod notifications;
+mod schedule;
+mod accounts;
+mod asset_image;
 
 use base64::decode;
-use bbs_shared::{data::ClassEntry, errors::LoginError, ClassID, FrontendData, PageState, StateUpdateAction, DataUpdateAction, SectionID};
+use bbs_shared::{accounts::{AccountID, AccountList, ChildID, ParentInfo}, data::{ClassEntry, Assignment, FeedItem, Instructor, MemberPage, Submission, UpcomingItem}, grades::GradeReport, settings::Settings, events::Event, errors::{AccountError, LoginError, MessageError, UploadError}, messages::{MessageThread, NewMessage, ThreadID, ThreadSummary}, notifications::Notification, ClassID, FrontendData, PageState, StateUpdateAction, DataUpdateAction, SectionID, MaterialID};
 use bincode::deserialize;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use std::{collections::HashSet, future::Future, sync::atomic::{AtomicU64, Ordering}};
 
-pub use login::{ LoginPage, LoginOverlay, LoginOverlayProps };
+pub use login::{ LoginPage, LoginOverlay, LoginOverlayProps, VerifyCodePage };
 pub use main_page::MainPage;
-pub use class_page::{ClassPage, ClassPageOverlay, ClassPageOverlayProps};
+pub use class_page::{ClassPage, ClassPageOverlay, ClassPageOverlayProps, SubmissionDetails};
 pub use breadcrumbs::{ Breadcrumbs, Breadcrumb, BreadcrumbProps };
+pub use feed::{ HomeFeed, HomeFeedProps };
+pub use grades::{ GradeBreakdown, GradeBreakdownProps };
+pub use gpa::GpaWidget;
+pub use people::{ ClassPeople, ClassPeopleProps };
+pub use inbox::{ InboxPage, InboxPageProps, InboxWidget };
+pub use notifications::NotificationBell;
+pub use schedule::ScheduleWidget;
+pub use accounts::{AccountSwitcher, ChildSwitcher};
+pub use asset_image::AssetImage;
 
 
 use wasm_bindgen::prelude::*;
@@ -46,18 +67,120 @@ extern "C" {
     #[wasm_bindgen(js_name = invokeIsLoggedIn, catch)]
     pub async fn is_logged_in_foreign() -> Result<JsValue, JsValue>;
     #[wasm_bindgen(js_name = invokeSetCredentials, catch)]
-    pub async fn set_credentials_foreign(username: String, password: String) -> Result<(), JsValue>;
+    pub async fn set_credentials_foreign(username: String, password: String, remember: bool) -> Result<(), JsValue>;
+    #[wasm_bindgen(js_name = invokeSubmitLoginCode, catch)]
+    pub async fn submit_login_code_foreign(code: String) -> Result<(), JsValue>;
+    #[wasm_bindgen(js_name = invokeCancelLoginCode, catch)]
+    pub async fn cancel_login_code_foreign() -> Result<(), JsValue>;
+    #[wasm_bindgen(js_name = invokeStartSso, catch)]
+    pub async fn start_sso_foreign() -> Result<(), JsValue>;
+    #[wasm_bindgen(js_name = invokeSetRememberPassword, catch)]
+    pub async fn set_remember_password_foreign(remember: bool) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_name = invokeListAccounts, catch)]
+    pub async fn list_accounts_foreign() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeSwitchAccount, catch)]
+    pub async fn switch_account_foreign(accountid: String) -> Result<(), JsValue>;
+    #[wasm_bindgen(js_name = invokeAddAccount, catch)]
+    pub async fn add_account_foreign() -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_name = invokeLogout, catch)]
+    pub async fn logout_foreign(everywhere: bool) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetChildren, catch)]
+    pub async fn get_children_foreign() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeSwitchChild, catch)]
+    pub async fn switch_child_foreign(childid: String) -> Result<(), JsValue>;
 
     #[wasm_bindgen(js_name = invokeGetClassListing, catch)]
     pub async fn get_class_listing_foreign() -> Result<JsValue, JsValue>;
     #[wasm_bindgen(js_name = parseSingleClassInfo, catch)]
     pub async fn parse_single_class_info(classid: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetClassInstructors, catch)]
+    pub async fn get_class_instructors_foreign(sectionid: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetClassMembers, catch)]
+    pub async fn get_class_members_foreign(sectionid: String, search: String, page: u32) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetUpcoming, catch)]
+    pub async fn get_upcoming_foreign() -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetNotifications, catch)]
+    pub async fn get_notifications_foreign() -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetInbox, catch)]
+    pub async fn get_inbox_foreign() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeGetMessageThread, catch)]
+    pub async fn get_message_thread_foreign(threadid: String) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeSendMessage, catch)]
+    pub async fn send_message_foreign(message: String) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeReplyMessage, catch)]
+    pub async fn reply_message_foreign(threadid: String, body: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetSubmission, catch)]
+    pub async fn get_submission_foreign(materialid: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = pickSubmissionFiles, catch)]
+    pub async fn pick_submission_files() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeSubmitAssignment, catch)]
+    pub async fn submit_assignment_foreign(materialid: String, upload: u64, paths: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = listenEvents, catch)]
+    pub async fn listen_events_foreign(callback: &Closure<dyn FnMut(JsValue)>) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetHomeFeed, catch)]
+    pub async fn get_home_feed_foreign() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeGetGrades, catch)]
+    pub async fn get_grades_foreign() -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = invokeGetSettings, catch)]
+    pub async fn get_settings_foreign() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = invokeSetSettings, catch)]
+    pub async fn set_settings_foreign(settings: String) -> Result<(), JsValue>;
 }
+
+/// Bumped whenever the frontend's data stops belonging to whoever is signed in: switching accounts or
+/// children, adding an account, logging out. Anything fetched before a bump is dropped when it arrives.
+static ACCOUNT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Awaits `fetch`, or gives `None` if the account changed in the meantime.
+async fn for_account<T>(fetch: impl Future<Output = T>) -> Option<T> {
+    let generation = ACCOUNT_GENERATION.load(Ordering::SeqCst);
+    let result = fetch.await;
+    (ACCOUNT_GENERATION.load(Ordering::SeqCst) == generation).then(|| result)
+}
+
+async fn invoke_for_account<T: DeserializeOwned>(invoke: impl Future<Output = Result<JsValue, JsValue>>) -> Option<Result<T, String>> {
+    for_account(invoke).await.map(decode_invoke_result)
+}
+
+/// Forgets everything that belonged to the previous account, including whatever is still on its way.
+fn reset_account_data(data_handle: &UseReducerHandle<FrontendData>) {
+    ACCOUNT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    data_handle.dispatch(DataUpdateAction::ClearAccountData);
+
+    let settings_handle = data_handle.clone();
+    get_settings(Callback::from(move |settings| settings_handle.dispatch(DataUpdateAction::SetSettings(settings))));
+}
+
+fn decode_invoke_result<T: DeserializeOwned>(result: Result<JsValue, JsValue>) -> Result<T, String> {
+    let data_str = result
+        .map_err(|err| format!("{:?}", err))?
+        .as_string()
+        .ok_or_else(|| "Returned value was not a string".to_string())?;
+
+    let data_buf = decode(&data_str).map_err(|err| err.to_string())?;
+
+    deserialize(&data_buf).map_err(|err| err.to_string())
+}
+
 pub fn get_class_listing(data_callback: Callback<Vec<ClassEntry>>, error_callback: Callback<LoginError>) {
     async fn get_class_listing_guts(data_callback: Callback<Vec<ClassEntry>>, error_callback: Callback<LoginError>) {
-        let opt_str = match get_class_listing_foreign().await {
-            Ok(val) => val.as_string(),
-            Err(err) => {
+        let opt_str = match for_account(get_class_listing_foreign()).await {
+            Some(Ok(val)) => val.as_string(),
+            None => return,
+            Some(Err(err)) => {
                 match err.as_string().map(TryFrom::try_from) {
                     Some(Ok(err)) => error_callback.emit(err),
                     Some(Err(de_err)) => window()
@@ -107,21 +230,233 @@ pub fn get_class_listing(data_callback: Callback<Vec<ClassEntry>>, error_callbac
     });
 }
 
-pub async fn is_logged_in() -> bool {
-    let string = match is_logged_in_foreign().await {
-        Ok(js_val) => match js_val.as_string() {
-            Some(string) => string,
-            None => return false,
+/// Errors are only passed on when the backend could say what went wrong, e.g. Schoology being down.
+pub async fn is_logged_in() -> Result<bool, LoginError> {
+    match is_logged_in_foreign().await {
+        Ok(js_val) => Ok(js_val
+            .as_string()
+            .and_then(|string| decode(&string).ok())
+            .and_then(|buffer| deserialize(&buffer).ok())
+            .unwrap_or(false)),
+        Err(err) => match err.as_string().map(LoginError::try_from) {
+            Some(Ok(err)) => Err(err),
+            _ => Ok(false),
         },
-        Err(_) => return false,
-    };
+    }
+}
+
+/// Checks the active account's session, landing on the main page or falling back to the login page.
+pub fn dispatch_sign_in(state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match is_logged_in().await {
+            Ok(true) => {
+                let failure_state_handle = state_handle.clone();
+
+                get_class_listing(
+                    Callback::from(move |new_data| {
+                        state_handle.dispatch(StateUpdateAction::ToMain);
+                        data_handle.dispatch(DataUpdateAction::SetClassListing(new_data));
+                    }),
+                    Callback::from(move |error| failure_state_handle.dispatch(StateUpdateAction::FailLogin(error))),
+                );
+            },
+            Ok(false) => state_handle.dispatch(StateUpdateAction::ToLogin),
+            Err(error) => state_handle.dispatch(StateUpdateAction::FailLogin(error)),
+        }
+    });
+}
 
-    let buffer = match decode(&string) {
-        Ok(buffer) => buffer,
-        Err(_) => return false,
+pub fn get_accounts(data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match decode_invoke_result::<AccountList>(list_accounts_foreign().await) {
+            Ok(accounts) => data_handle.dispatch(DataUpdateAction::SetAccounts(accounts)),
+            Err(err) => console::error_2(&"Failed to load accounts:".into(), &err.into()),
+        }
+    });
+}
+
+/// The backend swaps its client and cache; everything the frontend holds belonged to the old account.
+pub fn switch_account(account: AccountID, state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        if let Err(err) = switch_account_foreign(account.0).await {
+            console::error_2(&"Failed to switch accounts:".into(), &err);
+            return;
+        }
+
+        reset_account_data(&data_handle);
+        state_handle.dispatch(StateUpdateAction::LogIn);
+        get_accounts(data_handle.clone());
+        dispatch_sign_in(state_handle, data_handle);
+    });
+}
+
+pub fn add_account(state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        if let Err(err) = add_account_foreign().await {
+            console::error_2(&"Failed to add account:".into(), &err);
+            return;
+        }
+
+        reset_account_data(&data_handle);
+        get_accounts(data_handle);
+        state_handle.dispatch(StateUpdateAction::ToLogin);
+    });
+}
+
+/// A wrong code lands back on the code page through `FailLogin(InvalidCode)`.
+pub fn submit_login_code(code: String, state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        state_handle.dispatch(StateUpdateAction::LogIn);
+
+        match submit_login_code_foreign(code).await {
+            Ok(()) => dispatch_sign_in(state_handle, data_handle),
+            Err(err) => match err.as_string().map(LoginError::try_from) {
+                Some(Ok(error)) => state_handle.dispatch(StateUpdateAction::FailLogin(error)),
+                _ => {
+                    console::error_2(&"Failed to submit login code:".into(), &err);
+                    state_handle.dispatch(StateUpdateAction::FailLogin(LoginError::DecodeError(format!("{:?}", err))));
+                },
+            },
+        }
+    });
+}
+
+/// Gives up on the verification code and goes back to the password form.
+pub fn cancel_login_code(state_handle: UseReducerHandle<PageState>) {
+    spawn_local(async move {
+        if let Err(err) = cancel_login_code_foreign().await {
+            console::error_2(&"Failed to cancel login code:".into(), &err);
+        }
+
+        state_handle.dispatch(StateUpdateAction::ToLogin);
+    });
+}
+
+/// Only opens the window; the backend reports back with `Event::SsoFinished`.
+pub fn start_sso() {
+    spawn_local(async move {
+        if let Err(err) = start_sso_foreign().await {
+            console::error_2(&"Failed to open SSO window:".into(), &err);
+        }
+    });
+}
+
+/// Picks up where `start_sso` left off once the SSO window has closed.
+pub fn finish_sso(error: Option<LoginError>, state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    match error {
+        Some(error) => state_handle.dispatch(StateUpdateAction::FailLogin(error)),
+        None => {
+            state_handle.dispatch(StateUpdateAction::LogIn);
+            get_accounts(data_handle.clone());
+            dispatch_sign_in(state_handle, data_handle);
+        },
+    }
+}
+
+pub fn set_remember_password(remember: bool, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        if let Err(err) = set_remember_password_foreign(remember).await {
+            match err.as_string().map(AccountError::try_from) {
+                Some(Ok(err)) => window()
+                    .unwrap()
+                    .alert_with_message(&err.to_string())
+                    .unwrap(),
+                _ => console::error_2(&"Failed to update remembered password:".into(), &err),
+            }
+        }
+
+        get_accounts(data_handle);
+    });
+}
+
+/// The local session is gone even if telling Schoology fails, so the login page shows either way.
+pub fn logout(everywhere: bool, state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        if let Err(err) = logout_foreign(everywhere).await {
+            console::error_2(&"Failed to log out cleanly:".into(), &err);
+        }
+
+        reset_account_data(&data_handle);
+        get_accounts(data_handle);
+        state_handle.dispatch(StateUpdateAction::ToLogin);
+    });
+}
+
+pub fn get_children(data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<ParentInfo>(get_children_foreign()).await {
+            Some(Ok(children)) => data_handle.dispatch(DataUpdateAction::SetChildren(children)),
+            Some(Err(err)) => console::error_2(&"Failed to load children:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+/// Like switching accounts, except the backend keeps the session and only changes whose data it serves.
+pub fn switch_child(child: ChildID, state_handle: UseReducerHandle<PageState>, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        if let Err(err) = switch_child_foreign(child.0.to_string()).await {
+            console::error_2(&"Failed to switch child:".into(), &err);
+            return;
+        }
+
+        reset_account_data(&data_handle);
+        state_handle.dispatch(StateUpdateAction::LogIn);
+        get_children(data_handle.clone());
+        dispatch_sign_in(state_handle, data_handle);
+    });
+}
+
+async fn load_class_inner(
+    (id, section_id): (ClassID, SectionID),
+    item: Option<MaterialID>,
+    state_handle: UseReducerHandle<PageState>,
+    data_handle: UseReducerHandle<FrontendData>,
+) {
+    console::log_1(&id.0.to_string().as_str().into());
+    let data = match for_account(parse_single_class_info(section_id.0.to_string())).await {
+        None => return,
+        Some(Ok(data)) => {
+            if let Some(data) = data.as_string() {
+                data
+            } else {
+                console::error_2(&"step 2".into(), &data);
+                state_handle.dispatch(StateUpdateAction::ToMain);
+                return;
+            }
+        },
+        Some(Err(err)) => {
+            console::error_2(&"step 1".into(), &err);
+            state_handle.dispatch(StateUpdateAction::ToMain);
+            return;
+        }
+    };
+    let data = match decode(data) {
+        Ok(data) => data,
+        Err(err) => {
+            console::error_2(&"step 3".into(), &err.to_string().into());
+            state_handle.dispatch(StateUpdateAction::ToMain);
+            return;
+        }
     };
 
-    deserialize(&buffer).unwrap_or(false)
+    let item = match deserialize::<Vec<Vec<Assignment>>>(&data) {
+        Ok(materials_data) => {
+            let materials: Vec<_> = materials_data.into_iter().flatten().collect();
+            let item = item.filter(|item| materials.iter().any(|material| &material.id == item));
+            data_handle.dispatch(DataUpdateAction::SetClassPageInfo(materials));
+            item
+        },
+        Err(err) => {
+            console::error_2(&"step 4".into(), &err.to_string().into());
+            state_handle.dispatch(StateUpdateAction::ToMain);
+            return;
+        }
+    };
+    state_handle.dispatch(StateUpdateAction::ToClass(id));
+    if let Some(item) = item {
+        state_handle.dispatch(StateUpdateAction::ToClassItem(item));
+    }
 }
 
 pub fn dispatch_load_class(
@@ -129,46 +464,291 @@ pub fn dispatch_load_class(
     state_handle: UseReducerHandle<PageState>,
     data_handle: UseReducerHandle<FrontendData>,
 ) {
-    async fn dispatch_load_class_inner(
-        (id, section_id): (ClassID, SectionID),
-        state_handle: UseReducerHandle<PageState>,
-        data_handle: UseReducerHandle<FrontendData>,
-    ) {
-        console::log_1(&id.0.to_string().as_str().into());
-        let data = match parse_single_class_info(section_id.0.to_string()).await {
-            Ok(data) => {
-                if let Some(data) = data.as_string() {
-                    data
-                } else {
-                    console::error_2(&"step 2".into(), &data);
-                    state_handle.dispatch(StateUpdateAction::ToMain);
-                    return;
-                }
-            },
+    spawn_local(load_class_inner(ids, None, state_handle, data_handle));
+}
+
+pub fn dispatch_load_class_item(
+    ids: (ClassID, SectionID),
+    item: MaterialID,
+    state_handle: UseReducerHandle<PageState>,
+    data_handle: UseReducerHandle<FrontendData>,
+) {
+    spawn_local(load_class_inner(ids, Some(item), state_handle, data_handle));
+}
+
+/// Opens the class for `section`, or one of its materials, if the section is in the class listing.
+pub fn dispatch_open_section(
+    section: SectionID,
+    material: Option<MaterialID>,
+    state_handle: UseReducerHandle<PageState>,
+    data_handle: UseReducerHandle<FrontendData>,
+) {
+    let entry = data_handle
+        .classes
+        .borrow()
+        .as_ref()
+        .and_then(|classes| classes
+            .iter()
+            .find(|entry| entry.section_nid == section)
+            .map(|entry| (entry.id, entry.section_nid))
+        );
+
+    if let Some(ids) = entry {
+        state_handle.dispatch(StateUpdateAction::LoadClass(ids.0));
+        match material {
+            Some(material) => dispatch_load_class_item(ids, material, state_handle, data_handle),
+            None => dispatch_load_class(ids, state_handle, data_handle),
+        }
+    }
+}
+
+pub fn get_home_feed(data_callback: Callback<Vec<FeedItem>>) {
+    spawn_local(async move {
+        match invoke_for_account(get_home_feed_foreign()).await {
+            Some(Ok(feed)) => data_callback.emit(feed),
+            Some(Err(err)) => console::error_2(&"Failed to load home feed:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_grades(data_callback: Callback<Vec<GradeReport>>) {
+    spawn_local(async move {
+        match invoke_for_account(get_grades_foreign()).await {
+            Some(Ok(grades)) => data_callback.emit(grades),
+            Some(Err(err)) => console::error_2(&"Failed to load grades:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_settings(data_callback: Callback<Settings>) {
+    spawn_local(async move {
+        match invoke_for_account(get_settings_foreign()).await {
+            Some(Ok(settings)) => data_callback.emit(settings),
+            Some(Err(err)) => console::error_2(&"Failed to load settings:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+/// Applies `update` locally right away, then persists the result through the backend.
+pub fn update_settings(data_handle: UseReducerHandle<FrontendData>, update: impl FnOnce(&mut Settings)) {
+    let mut settings = data_handle.settings.borrow().clone().unwrap_or_default();
+    update(&mut settings);
+
+    data_handle.dispatch(DataUpdateAction::SetSettings(settings.clone()));
+
+    spawn_local(async move {
+        let encoded = match settings.to_base64() {
+            Ok(encoded) => encoded,
             Err(err) => {
-                console::error_2(&"step 1".into(), &err);
-                state_handle.dispatch(StateUpdateAction::ToMain);
+                console::error_2(&"Failed to encode settings:".into(), &err.to_string().into());
                 return;
             }
         };
-        let data = match decode(data) {
-            Ok(data) => data,
+        if let Err(err) = set_settings_foreign(encoded).await {
+            console::error_2(&"Failed to save settings:".into(), &err);
+        }
+    });
+}
+
+/// The backend answers `None` while it scrapes, then emits `SingleClassListingTeachers`;
+/// calling this again from that event picks up the cached list.
+pub fn get_class_instructors(section_id: SectionID, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<Option<Vec<Instructor>>>(get_class_instructors_foreign(section_id.0.to_string())).await {
+            Some(Ok(Some(instructors))) => data_handle.dispatch(DataUpdateAction::SetClassInstructors(section_id, instructors)),
+            Some(Ok(None)) => (),
+            Some(Err(err)) => console::error_2(&"Failed to load instructors:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_class_members(section_id: SectionID, search: String, page: u32, data_handle: UseReducerHandle<FrontendData>) {
+    data_handle.dispatch(DataUpdateAction::RequestMemberPage(section_id, search.clone(), page));
+
+    spawn_local(async move {
+        match invoke_for_account::<MemberPage>(get_class_members_foreign(section_id.0.to_string(), search.clone(), page)).await {
+            Some(Ok(member_page)) => data_handle.dispatch(DataUpdateAction::AddMemberPage(section_id, search, page, member_page)),
+            Some(Err(err)) => {
+                console::error_2(&"Failed to load members:".into(), &err.into());
+                data_handle.dispatch(DataUpdateAction::FailMemberPage(section_id, page));
+            },
+            None => (),
+        }
+    });
+}
+
+pub fn get_submission(material_id: MaterialID, data_callback: Callback<Submission>) {
+    spawn_local(async move {
+        match invoke_for_account(get_submission_foreign(material_id.0.to_string())).await {
+            Some(Ok(submission)) => data_callback.emit(submission),
+            Some(Err(err)) => console::error_2(&"Failed to load submission:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+/// Subscribes to events pushed by the backend for the lifetime of the app.
+pub fn listen_events(event_callback: Callback<Event>) {
+    let closure = Closure::wrap(Box::new(move |payload: JsValue| {
+        match payload.as_string().map(|payload| Event::from_base64(payload.as_bytes())) {
+            Some(Ok(event)) => event_callback.emit(event),
+            Some(Err(err)) => console::error_2(&"Failed to decode event:".into(), &err.to_string().into()),
+            None => console::error_2(&"Event payload was not a string:".into(), &payload),
+        }
+    }) as Box<dyn FnMut(JsValue)>);
+
+    spawn_local(async move {
+        if let Err(err) = listen_events_foreign(&closure).await {
+            console::error_2(&"Failed to listen for events:".into(), &err);
+        }
+        closure.forget();
+    });
+}
+
+static NEXT_UPLOAD: AtomicU64 = AtomicU64::new(0);
+
+pub fn submit_assignment(material_id: MaterialID, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        let paths = match pick_submission_files().await {
+            Ok(paths) if js_sys::Array::is_array(&paths) && js_sys::Array::from(&paths).length() > 0 => paths,
+            Ok(_) => return,
             Err(err) => {
-                console::error_2(&"step 3".into(), &err.to_string().into());
-                state_handle.dispatch(StateUpdateAction::ToMain);
+                console::error_2(&"Failed to pick files:".into(), &err);
                 return;
             }
         };
 
-        match deserialize::<Vec<Vec<_>>>(&data) {
-            Ok(materials_data) => data_handle.dispatch(DataUpdateAction::SetClassPageInfo(materials_data.into_iter().flatten().collect())),
+        let upload = NEXT_UPLOAD.fetch_add(1, Ordering::Relaxed);
+        data_handle.dispatch(DataUpdateAction::StartUpload(upload));
+
+        let result = submit_assignment_foreign(material_id.0.to_string(), upload, paths).await;
+        data_handle.dispatch(DataUpdateAction::FinishUpload(upload));
+
+        match result {
+            Ok(submission) => match decode_invoke_result(Ok(submission)) {
+                Ok(submission) => data_handle.dispatch(DataUpdateAction::SetSubmission(material_id, submission)),
+                Err(err) => console::error_2(&"Failed to decode submission:".into(), &err.into()),
+            },
             Err(err) => {
-                console::error_2(&"step 4".into(), &err.to_string().into());
-                state_handle.dispatch(StateUpdateAction::ToMain);
-                return;
+                let message = match err.as_string().map(UploadError::try_from) {
+                    Some(Ok(err)) => err.to_string(),
+                    _ => format!("{:?}", err),
+                };
+                window()
+                    .unwrap()
+                    .alert_with_message(&format!("Failed to submit: {}", message))
+                    .unwrap();
             }
         }
-        state_handle.dispatch(StateUpdateAction::ToClass(id));
+    });
+}
+
+fn message_error_text(err: JsValue) -> String {
+    match err.as_string().map(MessageError::try_from) {
+        Some(Ok(err)) => err.to_string(),
+        _ => format!("{:?}", err),
     }
-    spawn_local(dispatch_load_class_inner(ids, state_handle, data_handle));
-}
\ No newline at end of file
+}
+
+pub fn get_upcoming(data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<Vec<UpcomingItem>>(get_upcoming_foreign()).await {
+            Some(Ok(upcoming)) => data_handle.dispatch(DataUpdateAction::SetUpcoming(upcoming)),
+            Some(Err(err)) => console::error_2(&"Failed to load upcoming items:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_notifications(data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<Vec<Notification>>(get_notifications_foreign()).await {
+            Some(Ok(notifications)) => {
+                // Schoology only lists recent notifications, so ids it dropped would otherwise pile up forever.
+                let fetched: HashSet<_> = notifications.iter().map(|notification| notification.id).collect();
+                let has_stale = data_handle
+                    .settings
+                    .borrow()
+                    .as_ref()
+                    .map_or(false, |settings| settings.read_notifications.iter().any(|id| !fetched.contains(id)));
+
+                data_handle.dispatch(DataUpdateAction::SetNotifications(notifications));
+
+                if has_stale {
+                    update_settings(data_handle.clone(), move |settings| settings.read_notifications.retain(|id| fetched.contains(id)));
+                }
+            },
+            Some(Err(err)) => console::error_2(&"Failed to load notifications:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_inbox(data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<Vec<ThreadSummary>>(get_inbox_foreign()).await {
+            Some(Ok(threads)) => data_handle.dispatch(DataUpdateAction::SetInbox(threads)),
+            Some(Err(err)) => console::error_2(&"Failed to load inbox:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+pub fn get_message_thread(thread: ThreadID, data_handle: UseReducerHandle<FrontendData>) {
+    spawn_local(async move {
+        match invoke_for_account::<MessageThread>(get_message_thread_foreign(thread.0.to_string())).await {
+            Some(Ok(thread)) => data_handle.dispatch(DataUpdateAction::SetMessageThread(thread)),
+            Some(Err(err)) => console::error_2(&"Failed to load message thread:".into(), &err.into()),
+            None => (),
+        }
+    });
+}
+
+/// `done_callback` gets `None` on success, or the error to show the user.
+pub fn send_message(message: NewMessage, done_callback: Callback<Option<String>>) {
+    spawn_local(async move {
+        let encoded = match message.to_base64() {
+            Ok(encoded) => encoded,
+            Err(err) => {
+                done_callback.emit(Some(err.to_string()));
+                return;
+            }
+        };
+        match send_message_foreign(encoded).await {
+            Ok(_) => done_callback.emit(None),
+            Err(err) => done_callback.emit(Some(message_error_text(err))),
+        }
+    });
+}
+
+pub fn reply_message(thread: ThreadID, body: String, data_handle: UseReducerHandle<FrontendData>, done_callback: Callback<Option<String>>) {
+    spawn_local(async move {
+        match reply_message_foreign(thread.0.to_string(), body).await {
+            Ok(updated) => match decode_invoke_result::<MessageThread>(Ok(updated)) {
+                Ok(updated) => {
+                    data_handle.dispatch(DataUpdateAction::SetMessageThread(updated));
+                    done_callback.emit(None);
+                },
+                Err(err) => done_callback.emit(Some(err)),
+            },
+            Err(err) => done_callback.emit(Some(message_error_text(err))),
+        }
+    });
+}
+
+/// The local date and time, as the webview sees it.
+pub fn now() -> NaiveDateTime {
+    let now = js_sys::Date::new_0();
+    NaiveDate::from_ymd_opt(now.get_full_year() as i32, now.get_month() + 1, now.get_date())
+        .and_then(|date| date.and_hms_opt(now.get_hours(), now.get_minutes(), now.get_seconds()))
+        .expect("js dates are always valid")
+}
+
+pub fn today() -> NaiveDate {
+    now().date()
+}
+
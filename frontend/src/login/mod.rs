@@ -1,39 +1,63 @@
 
 use bbs_shared::{PageState, StateUpdateAction, DataUpdateAction, errors::LoginError, FrontendData};
 use wasm_bindgen_futures::spawn_local;
-use yew::{function_component, Properties, html, use_context, UseReducerHandle, Callback};
+use yew::{function_component, Properties, html, use_context, use_state_eq, UseReducerHandle, Callback};
 
 use web_sys::{ window, HtmlElement, HtmlInputElement};
 use wasm_bindgen::JsCast;
 
-use crate::{build_classes, get_class_listing};
+use crate::{build_classes, cancel_login_code, get_accounts, get_class_listing, start_sso, submit_login_code};
 
 use super::set_credentials_foreign;
 
 
 
+/// The password is deliberately not here: it stays in its input until it's sent to the backend.
 #[derive(Debug, Properties, PartialEq, Eq)]
 pub struct LoginDataPageProps {
     pub username: String,
-    pub password: String,
 }
 
 const LOGIN_INPUT: &str = "w-64 h-8 rounded-md bg-slate-600 border-[1px] border-slate-500 p-2 m-1";
+const REMEMBER_LABEL: &str = "w-64 m-1 flex items-center gap-2 text-sm select-none";
+const SSO_BUTTON: &str = build_classes!(
+    "w-64 h-8 m-1 mt-3",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500 border-[1px] border-slate-500",
+    "transition-colors duration-150",
+    "text-sm",
+);
+const CANCEL_LINK: &str = build_classes!(
+    "m-1 mt-3",
+    "text-sm opacity-70 hover:opacity-100 hover:underline",
+);
 
 #[function_component(LoginPage)]
 pub fn login_page(props: &LoginDataPageProps) -> Html {
     let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
     let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
 
-    let LoginDataPageProps { username, password } = props;
+    // stays ticked for accounts that already chose to be remembered
+    let remember = use_state_eq(|| data
+        .accounts
+        .borrow()
+        .as_ref()
+        .and_then(|list| list.accounts.iter().find(|account| Some(&account.id) == list.active.as_ref()))
+        .map_or(false, |account| account.remember_password));
+
+    let LoginDataPageProps { username } = props;
     let sumbit_username = username.clone();
-    let sumbit_password = password.clone();
     
     let app_state_for_uname = state.clone();
-    let app_state_for_passw = state.clone();
 
     let app_state_for_entering = state;
 
+    let remember_for_toggle = remember.clone();
+    let remember_change_event = move |event: yew::events::Event| {
+        remember_for_toggle.set(event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().checked());
+    };
+    let remember_for_entering = remember.clone();
+
     let user_change_event = move |event: yew::events::InputEvent| {
         app_state_for_uname.dispatch(
             StateUpdateAction::SetUname(
@@ -57,14 +81,6 @@ pub fn login_page(props: &LoginDataPageProps) -> Html {
         }
     };
 
-    let pass_change_event = move |event: yew::events::InputEvent| {
-        app_state_for_passw.dispatch(
-            StateUpdateAction::SetPassw(
-                event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value()
-            )
-        )
-    };
-
     let pass_enter_event = move |event: yew::events::KeyboardEvent| {
 
         if event.key() == "Enter" {
@@ -74,16 +90,19 @@ pub fn login_page(props: &LoginDataPageProps) -> Html {
             let app_data = data.clone();
 
             let username = sumbit_username.clone();
-            let password = sumbit_password.clone();
+            let password = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+            let remember = *remember_for_entering;
 
             spawn_local(async move {
-                if let Err(e) = set_credentials_foreign(username, password).await {
+                if let Err(e) = set_credentials_foreign(username, password, remember).await {
                     window()
                         .unwrap()
                         .alert_with_message(&format!("Failed to set credentials! {:#?}", e))
                         .unwrap();
                 } else {
                     app_state_for_entering.dispatch(StateUpdateAction::LogIn);
+                    // the active account is now labelled with this username
+                    get_accounts(app_data.clone());
 
                     let _ = get_class_listing(
                         Callback::from(move |new_data| {
@@ -119,18 +138,84 @@ pub fn login_page(props: &LoginDataPageProps) -> Html {
                 value={ username.clone() } />
             <input
                 class={LOGIN_INPUT}
-                oninput={pass_change_event}
                 onkeyup={pass_enter_event}
                 id={"password-field"}
                 placeholder={"Password"}
-                type={"password"}
-                value={ password.clone() } />
+                type={"password"} />
+            <label class={REMEMBER_LABEL}>
+                <input type={"checkbox"} checked={*remember} onchange={remember_change_event} />
+                {"Remember password"}
+            </label>
+            <button class={SSO_BUTTON} onclick={|_| start_sso()}>
+                {"Sign in with Google, Microsoft or SSO"}
+            </button>
         </div>
     }
 }
 
 
 
+#[derive(Debug, Properties, PartialEq, Eq)]
+pub struct VerifyCodePageProps {
+    pub hint: String,
+    pub code: String,
+    pub rejected: bool,
+}
+
+#[function_component(VerifyCodePage)]
+pub fn verify_code_page(props: &VerifyCodePageProps) -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+
+    let VerifyCodePageProps { hint, code, rejected } = props;
+
+    let change_state = state.clone();
+    let code_change_event = move |event: yew::events::InputEvent| {
+        change_state.dispatch(
+            StateUpdateAction::SetCode(
+                event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value()
+            )
+        )
+    };
+
+    let submit_code = code.clone();
+    let submit_state = state.clone();
+    let code_enter_event = move |event: yew::events::KeyboardEvent| {
+        if event.key() == "Enter" && !submit_code.trim().is_empty() {
+            submit_login_code(submit_code.clone(), submit_state.clone(), data.clone());
+        }
+    };
+
+    let cancel_event = move |_| cancel_login_code(state.clone());
+
+    html! {
+        <div class={"flex flex-col items-center justify-center h-full"}>
+            <h1 class={"text-3xl font-sans text-center mb-3"}>{"Enter your verification code"}</h1>
+            <p class={"w-64 text-sm text-center mb-2"}>
+                {if hint.is_empty() { "Schoology sent a code to your email or authenticator app." } else { hint.as_str() }}
+            </p>
+            {if *rejected {
+                html! { <p class={"w-64 text-sm text-center text-red-400 mb-2"}>{"That code didn't work. Check it and try again."}</p> }
+            } else {
+                html! {}
+            }}
+            <input
+                class={LOGIN_INPUT}
+                oninput={code_change_event}
+                onkeyup={code_enter_event}
+                id={"code-field"}
+                placeholder={"Code"}
+                autocomplete={"one-time-code"}
+                inputmode={"numeric"}
+                value={ code.clone() } />
+            <button class={CANCEL_LINK} onclick={cancel_event}>
+                {"Back to sign in"}
+            </button>
+        </div>
+    }
+}
+
+
 #[derive(Debug, Properties, PartialEq)]
 pub struct LoginOverlayProps {
     pub loading: bool,
@@ -173,32 +258,93 @@ const HIDE_INNER: &str = build_classes!(
 #[function_component(LoginOverlay)]
 pub fn login_overlay(props: &LoginOverlayProps) -> Html {
     use LoginError::*;
-    let message = match props.error {
+    let message = match &props.error {
         Some(SerializationError) => html! {
             <h1 class="text-2xl">{"Internal error encountered."}</h1>
         },
-        Some(FindFormError) => unreachable!("Find form error should be propogated to logged in."),
-        Some(InvalidCredsError) => html! {
-            <h1>{"Invalid username or password."}</h1>
+        Some(NetworkUnreachable(reason)) => html! {
+            <>
+                <h1 class="text-2xl">{"Couldn't reach Schoology."}</h1>
+                <h3>{"Check your internet connection, then try again."}</h3>
+                <h3 class="text-sm opacity-70">{reason}</h3>
+            </>
+        },
+        Some(Maintenance { status }) => html! {
+            <>
+                <h1 class="text-2xl">{"Schoology is down for maintenance."}</h1>
+                <h3>{"Nothing's wrong on your end. Try again in a little while."}</h3>
+                <h3 class="text-sm opacity-70">{format!("Status {}", status)}</h3>
+            </>
+        },
+        Some(InvalidCredentials { username }) => html! {
+            <>
+                <h1 class="text-2xl">{"Invalid username or password."}</h1>
+                <h3>{format!("Schoology didn't accept the password for {}.", username)}</h3>
+                <h3>{"Usernames are usually your school email address."}</h3>
+            </>
+        },
+        Some(AccountLocked(message)) => html! {
+            <>
+                <h1 class="text-2xl">{"This account is locked."}</h1>
+                <h3>{"Wait a while before trying again, or ask your school to unlock it."}</h3>
+                {if message.is_empty() { html! {} } else { html! { <h3 class="text-sm opacity-70">{message}</h3> } }}
+            </>
+        },
+        Some(CaptchaRequired { url }) => html! {
+            <>
+                <h1 class="text-2xl">{"Schoology wants a CAPTCHA solved."}</h1>
+                <h3>{"Sign in once at schoology.com in your browser, then come back and try again."}</h3>
+                <h3 class="text-sm opacity-70">{url}</h3>
+            </>
+        },
+        Some(UnexpectedPage { url, status }) => html! {
+            <>
+                <h1 class="text-2xl">{"Schoology sent back a page the app doesn't recognize."}</h1>
+                <h3>{"Schoology may have changed its login. Please alert the developer if this issue persists."}</h3>
+                <h3 class="text-sm opacity-70">{format!("{} (status {})", url, status)}</h3>
+            </>
         },
-        Some(RequestError) => html! {
+        Some(CodeRequired { .. }) | Some(InvalidCode { .. }) => html! {},
+        Some(CodeExpired) => html! {
+            <>
+                <h1 class="text-2xl">{"The verification code expired."}</h1>
+                <h3>{"Sign in with your password again to get a new one."}</h3>
+            </>
+        },
+        Some(RequestError(reason)) => html! {
             <>
                 <h1 class=" text-2xl">{"Failed to reach server."}</h1>
                 <h3>{"Try checking your internet connection."}</h3>
+                <h3 class="text-sm opacity-70">{reason}</h3>
             </>
         },
-        Some(LaterRequestError) => html! {
+        Some(LaterRequestError(reason)) => html! {
             <>
                 <h1>{"Transiently failed to fulfill request."}</h1>
                 <h3>{"This may be a problem with your internet or with the app."}</h3>
                 <h3>{"Please alert the developer if this issue persists."}</h3>
+                <h3 class="text-sm opacity-70">{reason}</h3>
             </>
         },
-        Some(DecodeError) => html! {
+        Some(DecodeError(reason)) => html! {
             <>
                 <h1>{"Invalid response text."}</h1>
                 <h3>{"This is likely an issue with Schoology."}</h3>
                 <h3>{"Please alert the developer if this issue persists."}</h3>
+                <h3 class="text-sm opacity-70">{reason}</h3>
+            </>
+        },
+        Some(SsoRequired) => html! {
+            <>
+                <h1 class="text-2xl">{"Your school signs in through SSO."}</h1>
+                <h3>{"Use the \"Sign in with Google, Microsoft or SSO\" button instead."}</h3>
+            </>
+        },
+        Some(SsoFailed(reason)) => html! {
+            <>
+                <h1 class="text-2xl">{"Single sign-on didn't finish."}</h1>
+                <h3>{"Schoology didn't accept the sign in. Please try again."}</h3>
+                <h3 class="text-sm opacity-70">{reason}</h3>
             </>
         },
         Some(JsonError) => html! {
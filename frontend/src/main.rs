@@ -1,24 +1,18 @@
 use std::ops::Deref;
 
-use bbs_shared::data::Assignment;
-use bbs_shared::{StateUpdateAction, DataUpdateAction, MaterialID};
+use bbs_shared::data::{Assignment, AssignmentType};
+use bbs_shared::{StateUpdateAction, DataUpdateAction, MaterialID, SectionID, events::{Event, DataType}, schedule::DayFilter};
 use bbs_shared::{ PageState, FrontendData };
 
 use frontend::MainPage;
-use frontend::{LoginPage, LoginOverlay, LoginOverlayProps};
-use frontend::{ClassPageOverlayProps, ClassPageOverlay};
-use frontend::{BreadcrumbProps, Breadcrumbs};
+use frontend::{LoginPage, LoginOverlay, LoginOverlayProps, VerifyCodePage};
+use frontend::{ClassPageOverlayProps, ClassPageOverlay, SubmissionDetails};
+use frontend::{AccountSwitcher, AssetImage, ChildSwitcher, BreadcrumbProps, Breadcrumbs, GpaWidget, InboxPage, InboxWidget, NotificationBell, ScheduleWidget};
 
-use frontend::{is_logged_in, get_class_listing, parse_single_class_info, reducer_contexts};
+use frontend::{dispatch_sign_in, finish_sso, get_accounts, get_class_instructors, get_settings, listen_events, parse_single_class_info, reducer_contexts};
 
-use wasm_bindgen::JsValue;
 use yew::{prelude::*, props};
 
-use wasm_bindgen_futures::spawn_local;
-
-use web_sys::console;
-
-static DAY_NAMES: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -31,7 +25,6 @@ pub fn app() -> Html {
 
     let app_state = use_reducer_eq(|| PageState::LoggingIn {
         username: String::new(),
-        password: String::new(),
     });
 
     let app_data = use_reducer_eq(FrontendData::empty);
@@ -39,23 +32,21 @@ pub fn app() -> Html {
     let callback_app_state = app_state.clone();
     let callback_app_data = app_data.clone();
     use_effect_with_deps(move |_| {
-        spawn_local(async move {
-            if is_logged_in().await {
-                let success_callback_app_state = callback_app_state.clone();
-                let failure_callback_app_state = callback_app_state;
-
-                let _ = get_class_listing(
-                    Callback::from(move |new_data| {
-                        success_callback_app_state.dispatch(StateUpdateAction::ToMain);
-                        callback_app_data.dispatch(DataUpdateAction::SetClassListing(new_data));
-                    }),
-                    Callback::from(move |error| failure_callback_app_state.dispatch(StateUpdateAction::FailLogin(error))),
-                );
-
-            } else {
-                callback_app_state.dispatch(StateUpdateAction::ToLogin);
-            }
-        });
+        let settings_app_data = callback_app_data.clone();
+        get_settings(Callback::from(move |settings| settings_app_data.dispatch(DataUpdateAction::SetSettings(settings))));
+
+        let event_app_state = callback_app_state.clone();
+        let event_app_data = callback_app_data.clone();
+        listen_events(Callback::from(move |event| match event {
+            Event::SsoFinished(error) => finish_sso(error, event_app_state.clone(), event_app_data.clone()),
+            Event::AssetReady(asset) => event_app_data.dispatch(DataUpdateAction::AssetReady(asset)),
+            Event::UploadProgress(progress) => event_app_data.dispatch(DataUpdateAction::SetUploadProgress(progress)),
+            Event::NewDataReady { data_type: DataType::SingleClassListingTeachers { id } } => get_class_instructors(SectionID(id), event_app_data.clone()),
+            _ => (),
+        }));
+
+        get_accounts(callback_app_data.clone());
+        dispatch_sign_in(callback_app_state, callback_app_data);
         || ()
     }, ());
 
@@ -64,8 +55,6 @@ pub fn app() -> Html {
 
     let home_callback: Callback<()> = (move |_| home_callback_app_state.dispatch(StateUpdateAction::ToMain)).into();
 
-    console::log_1(&JsValue::from_str(&format!("{:?}", app_state)));
-
     use PageState::*;
 
     let login_overlay_props;
@@ -75,7 +64,6 @@ pub fn app() -> Html {
     let inner = match app_state.deref() {
         Login {
             username,
-            password,
         } => {
             login_overlay_props = LoginOverlayProps {
                 loading: false,
@@ -89,13 +77,12 @@ pub fn app() -> Html {
                 return_to_login: None,
             };
             html! { <>
-                <LoginPage username={username.clone()} password={password.clone()} />
+                <LoginPage username={username.clone()} />
             </> }
         },
 
         LoggingIn {
             username,
-            password,
         } => {
             login_overlay_props = LoginOverlayProps {
                 loading: true,
@@ -109,19 +96,18 @@ pub fn app() -> Html {
                 return_to_login: None,
             };
             html! { <>
-                <LoginPage username={username.clone()} password={password.clone()} />
+                <LoginPage username={username.clone()} />
             </> }
         }
 
         LoginFailed {
             username,
-            password,
             reason,
         } => {
             let return_app_state = app_state.clone();
             login_overlay_props = LoginOverlayProps {
                 loading: false,
-                error: Some(*reason),
+                error: Some(reason.clone()),
                 return_to_login: Some(Callback::from(move |_| return_app_state.dispatch(StateUpdateAction::ReturnLogin))),
             };
             breadcrumbs = None;
@@ -131,24 +117,49 @@ pub fn app() -> Html {
                 return_to_login: None,
             };
             html! { <>
-                <LoginPage username={username.clone()} password={password.clone()} />
+                <LoginPage username={username.clone()} />
             </> }
         },
 
-        Main { day } => {
+        VerifyingCode {
+            hint,
+            code,
+            rejected,
+            ..
+        } => {
             login_overlay_props = LoginOverlayProps {
                 loading: false,
                 error: None,
                 return_to_login: None,
             };
-            breadcrumbs = if let Some(day) = day {
+            breadcrumbs = None;
+            class_page_overlay_props = ClassPageOverlayProps {
+                loading: false,
+                error: None,
+                return_to_login: None,
+            };
+            html! { <>
+                <VerifyCodePage hint={hint.clone()} code={code.clone()} rejected={*rejected} />
+            </> }
+        },
+
+        Main { filter } => {
+            login_overlay_props = LoginOverlayProps {
+                loading: false,
+                error: None,
+                return_to_login: None,
+            };
+            breadcrumbs = if let Some(filter) = filter {
                 Some(vec![
                     props!(BreadcrumbProps {
                         text: "Home",
                         on_click_callback: home_callback,
                     }),
                     props!(BreadcrumbProps {
-                        text: DAY_NAMES[*day].to_string(),
+                        text: match filter {
+                            DayFilter::Date(date) => date.format("%A, %B %-d").to_string(),
+                            DayFilter::Letter(letter) => format!("{} day", letter),
+                        },
                         on_click_callback: Callback::<()>::from(|_| ()),
                         has_next: false,
                     }),
@@ -169,7 +180,7 @@ pub fn app() -> Html {
             };
             html! { <>  
                 <div>
-                    <MainPage day={*day} classes={app_data.classes.clone()} key=0u8/>
+                    <MainPage filter={*filter} classes={app_data.classes.clone()} key=0u8/>
                 </div>
             </>}
         },
@@ -212,12 +223,13 @@ pub fn app() -> Html {
                 return_to_login: None,
             };
             html! {
-                <MainPage day={None} classes={app_data.classes.clone()} key=0u8/>
+                <MainPage filter={None} classes={app_data.classes.clone()} key=0u8/>
             }
         },
         ClassPage {
             id,
             expanded_folders: _,
+            tab,
         } => {
             let class_name = app_data
                 .classes
@@ -254,11 +266,24 @@ pub fn app() -> Html {
                 error: None,
                 return_to_login: None,
             };
+            let entry = app_data
+                .classes
+                .borrow()
+                .as_ref()
+                .and_then(|classes| classes.iter().find(|entry| &entry.id == id).cloned());
+            let grades = entry
+                .as_ref()
+                .and_then(|entry| app_data.grade_report(entry.section_nid));
+
             use frontend::ClassPage;
             html! {
                 <div>
                     <ClassPage
-                        materials={app_data.curr_class_data.clone()}/>
+                        materials={app_data.curr_class_data.clone()}
+                        grades={grades}
+                        section={entry.as_ref().map(|entry| entry.section_nid)}
+                        instructors={entry.and_then(|entry| entry.instructors)}
+                        tab={*tab}/>
                 </div>
             }
         },
@@ -342,16 +367,54 @@ pub fn app() -> Html {
                     <h1 style="text-align: center; padding-top:1.5%; padding-bottom:.5%; font-size: 20px;"><strong>{assignment.title}</strong></h1>
                     <h6 style="text-align: center; padding-bottom:1.8%; font-size: 12px">{assignment.duedate}</h6>
                     <p style="text-align: center;">{assignment.body}</p>
+                    {assignment.images
+                        .iter()
+                        .map(|image| html! { <AssetImage class="mx-auto mt-2 max-w-[80%] rounded-md" src={image.clone()}/> })
+                        .collect::<Html>()}
+                    {if assignment.kind == AssignmentType::Assignment {
+                        html! { <SubmissionDetails material_id={assignment.id} submission={assignment.submission} /> }
+                    } else {
+                        html! {}
+                    }}
                 </div>
             }
         },
+        Inbox {
+            thread,
+            composing,
+        } => {
+            login_overlay_props = LoginOverlayProps {
+                loading: false,
+                error: None,
+                return_to_login: None,
+            };
+            class_page_overlay_props = ClassPageOverlayProps {
+                loading: false,
+                error: None,
+                return_to_login: None,
+            };
+            breadcrumbs = Some(vec![
+                props!(BreadcrumbProps {
+                    text: "Home",
+                    on_click_callback: home_callback,
+                }),
+                props!(BreadcrumbProps {
+                    text: "Inbox",
+                    on_click_callback: Callback::<()>::from(|_| ()),
+                    has_next: false,
+                }),
+            ]);
+            html! {
+                <InboxPage thread={*thread} composing={*composing} />
+            }
+        },
     };
     
     reducer_contexts! { PageState: app_state, FrontendData: app_data =>
         <div class={"h-screen bg-slate-800 text-white overflow-scroll"}>
             <LoginOverlay ..login_overlay_props/>
             <ClassPageOverlay ..class_page_overlay_props/>
-            {if let Some(breadcrumbs) = breadcrumbs { html! {<Breadcrumbs children={breadcrumbs}/>} } else {  html! {} }}
+            {if let Some(breadcrumbs) = breadcrumbs { html! {<Breadcrumbs children={breadcrumbs} widgets={html! {<><AccountSwitcher/><ChildSwitcher/><ScheduleWidget/><NotificationBell/><InboxWidget/><GpaWidget/></>}}/>} } else {  html! {} }}
             {inner}
         </div>
     }
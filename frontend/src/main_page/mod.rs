@@ -1,8 +1,12 @@
-use bbs_shared::{data::{ClassEntry, SectionData, SectionDataGuts, OptMutComponent, Keyed}, PageState, StateUpdateAction, ClassID, FrontendData, SectionID};
-use web_sys::MouseEvent;
-use yew::{function_component, Properties, html, Html, use_context, UseReducerHandle, Callback};
+use std::collections::BTreeSet;
 
-use crate::dispatch_load_class;
+use bbs_shared::{data::{ClassEntry, SectionData, SectionDataGuts, OptMutComponent, Keyed}, grades::Grade, schedule::{DayFilter, LetterDay, PeriodTime}, section::Period, settings::SortMode, PageState, StateUpdateAction, DataUpdateAction, ClassID, FrontendData, SectionID};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlSelectElement, MouseEvent};
+use gloo_timers::callback::Interval;
+use yew::{function_component, Properties, html, Html, use_context, use_effect_with_deps, use_state_eq, UseReducerHandle, Callback};
+
+use crate::{dispatch_load_class, get_home_feed, get_grades, get_upcoming, get_class_instructors, now, update_settings, AssetImage, HomeFeed};
 
 #[macro_export]
 macro_rules! build_classes {
@@ -19,7 +23,12 @@ macro_rules! build_classes {
 #[derive(Debug, Properties, PartialEq)]
 pub struct MainPageClassProps {
     pub entry: ClassEntry,
+    pub grade: Option<Grade>,
     pub enabled: bool,
+    #[prop_or_default]
+    pub time: Option<PeriodTime>,
+    #[prop_or_default]
+    pub current: bool,
     pub into_class: Callback<(ClassID, SectionID)>,  
 }
 
@@ -55,13 +64,29 @@ const ISDAY_MAIN: &str = build_classes!(BOX_BASE, ISDAY_BASE, BOX_ANIM);
 const BOX_ACTIVATED_ISDAY: &str = build_classes!(ISDAY_MAIN, "bg-blue_400_saturated");
 const BOX_DEACTIVATED_ISDAY: &str = build_classes!(ISDAY_MAIN, "bg-slate-400");
 
+const FILTER_BASE: &str = build_classes!(
+    "px-3 py-1",
+    "rounded-md",
+    "text-sm",
+    "transition-colors duration-150",
+);
+const FILTER_ACTIVE: &str = build_classes!(FILTER_BASE, "bg-blue-400 text-black");
+const FILTER_INACTIVE: &str = build_classes!(FILTER_BASE, "bg-slate-600 hover:bg-slate-500");
+
 #[function_component(SectionDisplay)]
 pub fn section_display(props: &SectionData) -> Html {
     let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
 
-    let day = if let &PageState::Main { day } = &*state { day } else {None};
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let schedule = data.settings.borrow().clone().unwrap_or_default().schedule;
+
+    let day = if let &PageState::Main { filter: Some(filter) } = &*state {
+        schedule.resolve(filter)
+    } else {
+        None
+    };
 
-    let (days, period) = match &props.guts {
+    let (days, period, lab) = match &props.guts {
         SectionDataGuts::Bad(s) => return html! {
             <div class="h-full inline-flex items-center justify-center flex-col px-1.5 w-28">
                 <span>{s}</span>
@@ -69,24 +94,27 @@ pub fn section_display(props: &SectionData) -> Html {
         },
         SectionDataGuts::Good {
             days,
-            period
-        } => (days, period),
+            period,
+            lab,
+        } => (days, period, *lab),
     };
 
-    let indicators = days
-        .iter()
-        .copied()
-        .enumerate()
-        .skip(1).take(5)
-        .map(|(day_num, active)| {
+    // one box per letter in the rotation, plus any the section meets on outside it
+    let mut letters: BTreeSet<LetterDay> = schedule.rotation.iter().copied().collect();
+    letters.extend(days.iter().copied());
+
+    let indicators = letters
+        .into_iter()
+        .map(|letter| {
             let state = state.clone();
+            let active = days.contains(&letter);
             html! {
                 <div
-                    onclick={move |_| state.dispatch(StateUpdateAction::SetDayFilter(day_num))}
+                    onclick={move |_| state.dispatch(StateUpdateAction::SetDayFilter(DayFilter::Letter(letter)))}
                     class={
                         match (day, active) {
-                            (Some(day), true ) if day == day_num => BOX_ACTIVATED_ISDAY,
-                            (Some(day), false) if day == day_num => BOX_DEACTIVATED_ISDAY,
+                            (Some(day), true ) if day == letter => BOX_ACTIVATED_ISDAY,
+                            (Some(day), false) if day == letter => BOX_DEACTIVATED_ISDAY,
                             
                             (_, true) => BOX_ACTIVATED_NOTDAY,
                             (_, false) => BOX_DEACTIVATED_NOTDAY,
@@ -102,8 +130,14 @@ pub fn section_display(props: &SectionData) -> Html {
                 event.prevent_default();
                 event.stop_propagation();
             }}>
-            <span class="font-title font-medium text-xl" >{"Per. "}{period}</span>
-            <span class="pb-2">{if days != &[false; 7] {
+            <span class="font-title font-medium text-xl" >
+                {match period {
+                    Period::Numbered { .. } => format!("Per. {}", period),
+                    Period::Named(name) => name.clone(),
+                }}
+                {if lab { " Lab" } else { "" }}
+            </span>
+            <span class="pb-2">{if !days.is_empty() {
                 html! {<div>{indicators}</div>}
             } else {
                 html! {}
@@ -168,12 +202,21 @@ pub fn grade_indicator(props: &GradeIndicatorProps) -> Html {
 
 
 const CLASS_BASE: &str = build_classes!(
+    "flex flex-row items-center",
     "bg-opacity-0 bg-zinc-500 hover:bg-opacity-25",
     "[transition:background_200ms_ease-in-out_0s,height_300ms_ease-in-out_0s,transform_300ms_ease-in-out_0s]",
     "rounded-xl overflow-hidden",
 );
 const CLASS_SHOWING: &str = build_classes!(CLASS_BASE, "h-20 scale-y-100");
 const CLASS_HIDDEN: &str = build_classes!(CLASS_BASE, "h-0 scale-y-0");
+const CLASS_CURRENT: &str = build_classes!(CLASS_SHOWING, "ring-2 ring-inset ring-violet-400");
+
+fn format_countdown(seconds: i64) -> String {
+    match seconds {
+        s if s >= 3600 => format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60),
+        s => format!("{}:{:02}", s / 60, s % 60),
+    }
+}
 
 
 #[function_component(MainPageClass)]
@@ -186,32 +229,78 @@ pub fn main_page_class(props: &MainPageClassProps) -> Html {
     );
 
 
-    let name = &props.entry.name;
-    let is_ungraded = name.starts_with('~');
-    let without_tilde = name.strip_prefix('~').unwrap_or(name);
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let affects_gpa = data
+        .settings
+        .borrow()
+        .as_ref()
+        .map(|settings| settings.gpa.affects_gpa(&props.entry))
+        .unwrap_or_else(|| props.entry.affects_gpa());
+    let display_name = props.entry.display_name();
     let id = props.entry.id;
     let section_id = props.entry.section_nid;
     let callback = props.into_class.clone();
 
+    let instructors_data = data.clone();
+    let needs_instructors = props.entry.instructors.is_none();
+    use_effect_with_deps(move |section_id| {
+        if needs_instructors {
+            get_class_instructors(*section_id, instructors_data);
+        }
+        || ()
+    }, section_id);
+
     html! {
         <div
-            class={if props.enabled {CLASS_SHOWING} else {CLASS_HIDDEN}}
+            class={match (props.enabled, props.current) {
+                (true, true) => CLASS_CURRENT,
+                (true, false) => CLASS_SHOWING,
+                (false, _) => CLASS_HIDDEN,
+            }}
             onclick={move |_| callback.clone().emit((id, section_id))}>
             <SectionDisplay guts={props.entry.section.guts.clone()} />
+            {if let Some(picture) = &props.entry.picture {
+                html! { <AssetImage class="w-12 h-12 shrink-0 rounded-lg object-cover mr-5" src={picture.clone()}/> }
+            } else {
+                html! { <div class="w-12 h-12 shrink-0 rounded-lg bg-slate-700 mr-5"/> }
+            }}
             <div class={MAIN_BODY}>
                 <span class="flex flex-row text-2xl text-gray150 items-center">
-                    <GradeIndicator enabled={!is_ungraded}/>
-                    {without_tilde}
+                    <GradeIndicator enabled={affects_gpa}/>
+                    {display_name}
+                </span>
+                <span class="text-sm text-gray-400 rounded">
+                    {match &props.entry.instructors {
+                        Some(instructors) if !instructors.is_empty() => instructors
+                            .iter()
+                            .map(|instructor| instructor.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        _ => id.0.to_string(),
+                    }}
                 </span>
-                <span class="text-sm text-gray-400 rounded">{id.0}</span>
             </div>
+            {if let Some(time) = &props.time {
+                html! {
+                    <span class="ml-auto text-sm text-gray-400 shrink-0">
+                        {format!("{} – {}", time.start.format("%-I:%M"), time.end.format("%-I:%M"))}
+                    </span>
+                }
+            } else {
+                html! {}
+            }}
+            {if let Some(grade) = &props.grade {
+                html! { <span class="ml-auto mr-5 pl-5 text-xl font-medium shrink-0">{grade.to_string()}</span> }
+            } else {
+                html! {}
+            }}
         </div>
     }
 }
 
 #[derive(Debug, Properties, PartialEq)]
 pub struct MainPageProps {
-    pub day: Option<usize>,
+    pub filter: Option<DayFilter>,
     pub classes: Keyed<OptMutComponent<Vec<ClassEntry>>>,
 }
 
@@ -220,30 +309,148 @@ pub struct MainPageProps {
 pub fn main_page(props: &MainPageProps) -> Html {
     let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
     let data = use_context::<UseReducerHandle<FrontendData>>().expect("no state ctx found");
-    let day = props.day;
+    let settings = data.settings.borrow().clone().unwrap_or_default();
+    let schedule = settings.schedule;
+    let sort_mode = settings.sort_mode;
+    let filter = props.filter;
+    // `None` means show everything, `Some(None)` a date without school
+    let letter = filter.map(|filter| schedule.resolve(filter));
+    let clock = use_state_eq(now);
+    let today = clock.date();
+    let tomorrow = today.succ_opt().unwrap_or(today);
+
+    let tick_clock = clock.clone();
+    use_effect_with_deps(move |_| {
+        let interval = Interval::new(1000, move || tick_clock.set(now()));
+        move || drop(interval)
+    }, ());
+
+    // the date whose bell schedule applies; picking a bare letter has no date to look times up for
+    let schedule_date = match filter {
+        Some(DayFilter::Date(date)) => Some(date),
+        Some(DayFilter::Letter(_)) => None,
+        None => Some(today),
+    };
+    let time_of = |entry: &ClassEntry| match (&entry.section.guts, schedule_date) {
+        (SectionDataGuts::Good { period, .. }, Some(date)) if schedule.letter_for(date).map(|letter| entry.section.meets_on(letter)).unwrap_or(false) => {
+            // a block of periods runs from the start of its first to the end of its last
+            let (first, last) = period.numbers()?;
+            let end = schedule.period_time(date, last)?.end;
+            schedule.period_time(date, first).map(|time| PeriodTime { end, ..time })
+        },
+        _ => None,
+    };
+    let is_today = schedule_date == Some(today);
+    let time_now = clock.time();
+
+    let feed_data = data.clone();
+    use_effect_with_deps(move |_| {
+        if feed_data.home_feed.borrow().is_none() {
+            let feed_data = feed_data.clone();
+            get_home_feed(Callback::from(move |feed_items| feed_data.dispatch(DataUpdateAction::SetHomeFeed(feed_items))));
+        }
+        if feed_data.grades.borrow().is_none() {
+            let grades_data = feed_data.clone();
+            get_grades(Callback::from(move |grades| grades_data.dispatch(DataUpdateAction::SetGrades(grades))));
+        }
+        if feed_data.upcoming.borrow().is_none() {
+            get_upcoming(feed_data.clone());
+        }
+        || ()
+    }, ());
 
     let classes_ref = props.classes.borrow();
     
+    let mut timed: Vec<_> = classes_ref
+        .iter()
+        .flatten()
+        .map(|entry| (entry, time_of(entry)))
+        .collect();
+
+    // every mode sorts stably on top of the total order, so ties always land the same way
+    timed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    match sort_mode {
+        // a date (today when nothing is picked) shows the day as it happens, so put it in bell order
+        SortMode::Period => if schedule_date.is_some() {
+            timed.sort_by_key(|(_, time)| (time.is_none(), time.map(|time| time.start)));
+        },
+        SortMode::Name => timed.sort_by_key(|(entry, _)| entry.display_name().to_lowercase()),
+        SortMode::NextDue => timed.sort_by_key(|(entry, _)| {
+            let due = data.next_due(entry.section_nid);
+            (due.is_none(), due)
+        }),
+        SortMode::Grade => {
+            let percent_of = |entry: &ClassEntry| data
+                .grade_report(entry.section_nid)
+                .and_then(|report| report.current_grade()?.percent);
+            // highest first, ungraded classes last
+            timed.sort_by(|(a, _), (b, _)| match (percent_of(*a), percent_of(*b)) {
+                (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal),
+                (a, b) => a.is_none().cmp(&b.is_none()),
+            });
+        },
+    }
+
+    let upcoming = if is_today {
+        let current = timed
+            .iter()
+            .find(|(_, time)| matches!(time, Some(time) if time.start <= time_now && time_now < time.end))
+            .map(|(entry, time)| (*entry, time.unwrap(), true));
+        let next = timed
+            .iter()
+            .filter_map(|(entry, time)| Some((*entry, (*time)?)))
+            .filter(|(_, time)| time.start > time_now)
+            .min_by_key(|(_, time)| time.start)
+            .map(|(entry, time)| (entry, time, false));
+        current.or(next)
+    } else {
+        None
+    };
+    let current_id = upcoming.and_then(|(entry, _, is_current)| is_current.then(|| entry.id));
+
+    let countdown_html = match upcoming {
+        Some((entry, time, true)) => html! {
+            <div class="mx-5 mb-2 px-3 py-2 rounded-lg bg-slate-700">
+                <span class="font-medium">{entry.display_name()}</span>
+                {" ends in "}
+                <span class="font-mono">{format_countdown((time.end - time_now).num_seconds())}</span>
+            </div>
+        },
+        Some((entry, time, false)) => html! {
+            <div class="mx-5 mb-2 px-3 py-2 rounded-lg bg-slate-700">
+                {"Next: "}
+                <span class="font-medium">{entry.display_name()}</span>
+                {" in "}
+                <span class="font-mono">{format_countdown((time.start - time_now).num_seconds())}</span>
+            </div>
+        },
+        None => html! {},
+    };
+
     let class_html = match classes_ref.as_ref() {
-        Some(a) => html! {
+        Some(_) => html! {
             {
-                a
+                timed
                     .iter()
-                    .map(|entry| (
-                        entry,
-                        if let Some(day) = day {
-                            if let SectionDataGuts::Good { days, .. } = entry.section.guts {
-                                days[day]
-                            } else {false}
-                        } else {true}
+                    .map(|(entry, time)| (
+                        *entry,
+                        *time,
+                        match letter {
+                            Some(Some(letter)) => entry.section.meets_on(letter),
+                            Some(None) => false,
+                            None => true,
+                        }
                     ))
-                    .map(|(entry, enabled)| {
+                    .map(|(entry, time, enabled)| {
                         let state = state.clone();
                         let data = data.clone();
                         html! {
                             <MainPageClass
                                 entry={entry.clone()}
+                                grade={data.grade_report(entry.section_nid).and_then(|report| report.current_grade().cloned())}
                                 enabled={enabled}
+                                time={time}
+                                current={current_id == Some(entry.id)}
                                 key={entry.id.0}
                                 into_class={Callback::from(move |(class_id, section_id)| {
                                     state.dispatch(StateUpdateAction::LoadClass(class_id));
@@ -259,9 +466,58 @@ pub fn main_page(props: &MainPageProps) -> Html {
         },
     };
 
+    let filter_button = |label: &'static str, target: Option<DayFilter>| {
+        let state = state.clone();
+        html! {
+            <button
+                class={if filter == target { FILTER_ACTIVE } else { FILTER_INACTIVE }}
+                onclick={move |_: MouseEvent| match target {
+                    Some(target) => state.dispatch(StateUpdateAction::SetDayFilter(target)),
+                    None => state.dispatch(StateUpdateAction::ToMain),
+                }}>
+                {label}
+            </button>
+        }
+    };
+
+    let sort_data = data.clone();
+    let on_sort = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value();
+        if let Some(mode) = SortMode::ALL.into_iter().find(|mode| mode.label() == value) {
+            update_settings(sort_data.clone(), |settings| settings.sort_mode = mode);
+        }
+    };
+
     html! {
-        <div>
-            {class_html}
+        <div class="flex flex-row items-start">
+            <div class="flex-1 min-w-0">
+                <div class="flex flex-row items-center gap-2 px-5 py-2">
+                    {filter_button("All", None)}
+                    {filter_button("Today", Some(DayFilter::Date(today)))}
+                    {filter_button("Tomorrow", Some(DayFilter::Date(tomorrow)))}
+                    <span class="ml-2 text-sm text-gray-400">
+                        {match letter {
+                            Some(Some(letter)) => format!("{} day", letter),
+                            Some(None) => "No school".to_string(),
+                            None => String::new(),
+                        }}
+                    </span>
+                    <label class="ml-auto flex flex-row items-center gap-2 text-sm text-gray-400">
+                        {"Sort by"}
+                        <select class="px-2 py-0.5 rounded-md bg-slate-600 text-white" onchange={on_sort}>
+                            {SortMode::ALL
+                                .iter()
+                                .map(|mode| html! {
+                                    <option value={mode.label()} selected={*mode == sort_mode}>{mode.label()}</option>
+                                })
+                                .collect::<Html>()}
+                        </select>
+                    </label>
+                </div>
+                {countdown_html}
+                {class_html}
+            </div>
+            <HomeFeed items={data.home_feed.clone()} />
         </div>
     }
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use bbs_shared::{notifications::{unread_count, Notification}, FrontendData, PageState};
+use web_sys::MouseEvent;
+use yew::{function_component, html, Html, use_context, use_effect_with_deps, use_state_eq, UseReducerHandle};
+
+use crate::{build_classes, dispatch_open_section, get_notifications, update_settings};
+
+const BELL_BUTTON: &str = build_classes!(
+    "relative",
+    "px-2 py-0.5",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "transition-colors duration-150",
+    "text-sm whitespace-nowrap",
+);
+
+const COUNT_BADGE: &str = build_classes!(
+    "ml-1 px-1.5",
+    "rounded-full",
+    "bg-red-500 text-white",
+    "text-xs font-medium",
+);
+
+const DROPDOWN: &str = build_classes!(
+    "absolute right-0 top-full mt-1",
+    "w-96 max-h-[70vh] overflow-y-scroll",
+    "p-2",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+    "shadow-lg",
+    "z-30",
+);
+
+const ITEM_BASE: &str = build_classes!(
+    "flex flex-col",
+    "px-3 py-2",
+    "rounded-lg",
+    "cursor-pointer",
+    "hover:bg-slate-600",
+    "transition-colors duration-150",
+);
+const ITEM_UNREAD: &str = build_classes!(ITEM_BASE, "border-l-4 border-violet-400");
+const ITEM_READ: &str = build_classes!(ITEM_BASE, "text-gray-400");
+
+#[function_component(NotificationBell)]
+pub fn notification_bell() -> Html {
+    let state = use_context::<UseReducerHandle<PageState>>().expect("no state ctx found");
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let expanded = use_state_eq(|| false);
+
+    let mount_data = data.clone();
+    use_effect_with_deps(move |_| {
+        if mount_data.notifications.borrow().is_none() {
+            get_notifications(mount_data.clone());
+        }
+        || ()
+    }, ());
+
+    let read = data
+        .settings
+        .borrow()
+        .as_ref()
+        .map(|settings| settings.read_notifications.clone())
+        .unwrap_or_default();
+    let notifications = data.notifications.borrow().clone().unwrap_or_default();
+    let unread = unread_count(&notifications, &read);
+
+    let toggle_expanded = expanded.clone();
+    let toggle = move |_: MouseEvent| toggle_expanded.set(!*toggle_expanded);
+
+    let mark_all_data = data.clone();
+    let all_ids: Vec<_> = notifications.iter().map(|notification| notification.id).collect();
+    let mark_all = move |_: MouseEvent| {
+        let all_ids = all_ids.clone();
+        update_settings(mark_all_data.clone(), move |settings| settings.read_notifications.extend(all_ids));
+    };
+
+    let item_html = |notification: &Notification| {
+        let is_read = read.contains(&notification.id);
+        let open_state = state.clone();
+        let open_data = data.clone();
+        let open_expanded = expanded.clone();
+        let notification = notification.clone();
+        let id = notification.id;
+
+        let on_click = move |_: MouseEvent| {
+            update_settings(open_data.clone(), |settings| {
+                settings.read_notifications.insert(id);
+            });
+            if let Some(section) = notification.section {
+                open_expanded.set(false);
+                dispatch_open_section(section, notification.material, open_state.clone(), open_data.clone());
+            }
+        };
+
+        html! {
+            <div class={if is_read { ITEM_READ } else { ITEM_UNREAD }} onclick={on_click}>
+                <span class="text-xs uppercase text-gray-400">{notification.kind.label()}</span>
+                <span class="text-sm">{&notification.text}</span>
+                <span class="text-xs text-gray-500">{&notification.posted}</span>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="relative">
+            <button class={BELL_BUTTON} onclick={toggle}>
+                {"🔔"}
+                {if unread > 0 {
+                    html! { <span class={COUNT_BADGE}>{unread}</span> }
+                } else {
+                    html! {}
+                }}
+            </button>
+            {if *expanded {
+                html! {
+                    <div class={DROPDOWN}>
+                        <div class="flex flex-row items-center justify-between px-2 pb-2">
+                            <span class="font-medium">{"Notifications"}</span>
+                            <button class="text-sm text-blue-400 hover:underline" onclick={mark_all}>{"Mark all read"}</button>
+                        </div>
+                        {if notifications.is_empty() {
+                            html! { <div class="text-center text-gray-500">{"No notifications"}</div> }
+                        } else {
+                            notifications.iter().map(item_html).collect::<Html>()
+                        }}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
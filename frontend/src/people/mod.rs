@@ -0,0 +1,162 @@
+use bbs_shared::{data::{Member, MemberRole}, FrontendData, SectionID};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement, MouseEvent};
+use yew::{function_component, Properties, html, Html, use_context, use_effect_with_deps, use_state_eq, UseReducerHandle};
+
+use crate::{build_classes, get_class_members, AssetImage};
+
+const SEARCH_INPUT: &str = build_classes!(
+    "w-full h-9",
+    "px-3",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+);
+
+const MEMBER_ROW: &str = build_classes!(
+    "flex flex-row items-center gap-3",
+    "px-3 py-2",
+    "rounded-lg",
+    "bg-opacity-0 bg-zinc-500 hover:bg-opacity-25",
+    "transition-colors duration-200",
+);
+
+const LOAD_MORE: &str = build_classes!(
+    "self-center mt-2 px-3 py-1",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "disabled:opacity-50 disabled:cursor-default",
+    "text-sm",
+);
+
+fn member_html(member: &Member) -> Html {
+    html! {
+        <div class={MEMBER_ROW}>
+            {if let Some(avatar) = &member.avatar {
+                html! { <AssetImage class="w-9 h-9 rounded-full object-cover" src={avatar.clone()}/> }
+            } else {
+                html! { <div class="w-9 h-9 rounded-full bg-slate-600"/> }
+            }}
+            {if let Some(profile_url) = &member.profile_url {
+                html! { <a class="flex-1 hover:underline" href={profile_url.clone()} target="_blank">{&member.name}</a> }
+            } else {
+                html! { <span class="flex-1">{&member.name}</span> }
+            }}
+            <span class="text-xs uppercase text-gray-400">{member.role.label()}</span>
+        </div>
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct ClassPeopleProps {
+    pub section: SectionID,
+}
+
+#[function_component(ClassPeople)]
+pub fn class_people(props: &ClassPeopleProps) -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let search = use_state_eq(String::new);
+
+    let section = props.section;
+    let roster = data
+        .rosters
+        .borrow()
+        .as_ref()
+        .and_then(|rosters| rosters.get(&section).cloned());
+
+    let mount_data = data.clone();
+    let needs_roster = roster.is_none();
+    use_effect_with_deps(move |section| {
+        if needs_roster {
+            get_class_members(*section, String::new(), 1, mount_data);
+        }
+        || ()
+    }, section);
+
+    let search_state = search.clone();
+    let on_search = move |event: yew::events::InputEvent| {
+        search_state.set(event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value());
+    };
+
+    // Typing narrows what's loaded right away; the server search runs once the query is submitted.
+    let submit_data = data.clone();
+    let searched = roster.as_ref().map(|roster| roster.query.clone());
+    let on_submit = move |event: Event| {
+        let query = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value().trim().to_owned();
+        if searched.as_ref() != Some(&query) {
+            get_class_members(section, query, 1, submit_data.clone());
+        }
+    };
+
+    let search_html = html! {
+        <input
+            class={SEARCH_INPUT}
+            type="search"
+            placeholder="Search members"
+            value={(*search).clone()}
+            oninput={on_search}
+            onchange={on_submit} />
+    };
+
+    let roster = match roster {
+        Some(roster) if !(roster.members.is_empty() && roster.pending == Some(1)) => roster,
+        _ => return html! {
+            <div class="flex flex-col mx-5 my-3">
+                {search_html}
+                <div class="mt-3 text-center text-gray-500">{"Loading members..."}</div>
+            </div>
+        },
+    };
+
+    let query = search.trim().to_lowercase();
+    let matching: Vec<_> = roster.members
+        .iter()
+        .filter(|member| query.is_empty() || member.name.to_lowercase().contains(&query))
+        .collect();
+
+    let section_html = |role: MemberRole, title: &str| {
+        let members: Vec<_> = matching.iter().filter(|member| member.role == role).collect();
+        if members.is_empty() {
+            html! {}
+        } else {
+            html! {
+                <div class="flex flex-col mb-3">
+                    <span class="px-3 py-1 text-lg font-medium border-b-2 border-slate-500">
+                        {title}
+                        <span class="ml-2 text-sm text-gray-400">{members.len()}</span>
+                    </span>
+                    {members.iter().map(|member| member_html(member)).collect::<Html>()}
+                </div>
+            }
+        }
+    };
+
+    html! {
+        <div class="flex flex-col mx-5 my-3">
+            {search_html}
+            <div class="flex flex-col mt-3">
+                {section_html(MemberRole::Admin, "Teachers")}
+                {section_html(MemberRole::Member, "Students")}
+                {if matching.is_empty() {
+                    html! { <div class="text-center text-gray-500">{"No members found"}</div> }
+                } else {
+                    html! {}
+                }}
+            </div>
+            {if let Some(next_page) = roster.next_page {
+                let more_data = data.clone();
+                let more_query = roster.query.clone();
+                let loading = roster.pending.is_some();
+                html! {
+                    <button
+                        class={LOAD_MORE}
+                        disabled={loading}
+                        onclick={move |_: MouseEvent| get_class_members(section, more_query.clone(), next_page, more_data.clone())}>
+                        {if loading { "Loading..." } else { "Load more" }}
+                    </button>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
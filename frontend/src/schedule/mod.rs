@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use bbs_shared::{schedule::{BellSchedule, LetterDay, PeriodTime}, FrontendData};
+use chrono::{NaiveDate, NaiveTime};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, MouseEvent};
+use yew::{function_component, html, Html, use_context, use_state_eq, UseReducerHandle};
+
+use crate::{build_classes, today, update_settings};
+
+const WIDGET_BUTTON: &str = build_classes!(
+    "px-2 py-0.5",
+    "rounded-md",
+    "bg-slate-600 hover:bg-slate-500",
+    "transition-colors duration-150",
+    "text-sm whitespace-nowrap",
+);
+
+const DROPDOWN: &str = build_classes!(
+    "absolute right-0 top-full mt-1",
+    "w-80",
+    "p-3",
+    "flex flex-col gap-2",
+    "rounded-lg",
+    "bg-slate-700 border-[1px] border-slate-500",
+    "shadow-lg",
+    "z-30",
+    "text-sm",
+);
+
+const FIELD: &str = "w-full px-2 py-1 rounded-md bg-slate-600 border-[1px] border-slate-500";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn parse_dates(text: &str) -> HashSet<NaiveDate> {
+    text
+        .split(|c: char| c == '\n' || c == ',')
+        .filter_map(|line| NaiveDate::parse_from_str(line.trim(), DATE_FORMAT).ok())
+        .collect()
+}
+
+fn format_dates(dates: &HashSet<NaiveDate>) -> String {
+    let mut dates: Vec<_> = dates.iter().collect();
+    dates.sort();
+    dates
+        .iter()
+        .map(|date| date.format(DATE_FORMAT).to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Bell schedules are edited as text: a `[Name]` line, then one `<period> <start>-<end>` line per period.
+fn parse_bell_schedules(text: &str) -> Vec<BellSchedule> {
+    let mut schedules: Vec<BellSchedule> = Vec::new();
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            schedules.push(BellSchedule {
+                name: name.trim().to_owned(),
+                periods: Vec::new(),
+            });
+            continue;
+        }
+
+        let period_time = line
+            .split_once(char::is_whitespace)
+            .and_then(|(period, times)| {
+                let (start, end) = times.trim().split_once('-')?;
+                Some(PeriodTime {
+                    period: period.parse().ok()?,
+                    start: NaiveTime::parse_from_str(start.trim(), TIME_FORMAT).ok()?,
+                    end: NaiveTime::parse_from_str(end.trim(), TIME_FORMAT).ok()?,
+                })
+            });
+
+        if let (Some(period_time), Some(schedule)) = (period_time, schedules.last_mut()) {
+            schedule.periods.push(period_time);
+        }
+    }
+
+    schedules
+}
+
+fn format_bell_schedules(schedules: &[BellSchedule]) -> String {
+    schedules
+        .iter()
+        .map(|schedule| {
+            let periods = schedule.periods
+                .iter()
+                .map(|time| format!("{} {}-{}", time.period, time.start.format(TIME_FORMAT), time.end.format(TIME_FORMAT)));
+            std::iter::once(format!("[{}]", schedule.name))
+                .chain(periods)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[function_component(ScheduleWidget)]
+pub fn schedule_widget() -> Html {
+    let data = use_context::<UseReducerHandle<FrontendData>>().expect("no data ctx found");
+    let expanded = use_state_eq(|| false);
+
+    let schedule = data.settings.borrow().clone().unwrap_or_default().schedule;
+    let today_letter = schedule.letter_for(today());
+
+    let toggle_expanded = expanded.clone();
+    let toggle = move |_: MouseEvent| toggle_expanded.set(!*toggle_expanded);
+
+    let rotation_data = data.clone();
+    let on_rotation = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+        let rotation: Vec<_> = value.chars().filter_map(|letter| LetterDay::from_letter(letter.to_ascii_uppercase())).collect();
+        if !rotation.is_empty() {
+            update_settings(rotation_data.clone(), |settings| settings.schedule.rotation = rotation);
+        }
+    };
+
+    let start_data = data.clone();
+    let on_start = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+        let start_date = NaiveDate::parse_from_str(&value, DATE_FORMAT).ok();
+        update_settings(start_data.clone(), |settings| settings.schedule.start_date = start_date);
+    };
+
+    let holidays_data = data.clone();
+    let on_holidays = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlTextAreaElement>().unwrap().value();
+        update_settings(holidays_data.clone(), |settings| settings.schedule.holidays = parse_dates(&value));
+    };
+
+    let skipped_data = data.clone();
+    let on_skipped = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlTextAreaElement>().unwrap().value();
+        update_settings(skipped_data.clone(), |settings| settings.schedule.skipped_days = parse_dates(&value));
+    };
+
+    let bells_data = data.clone();
+    let on_bells = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlTextAreaElement>().unwrap().value();
+        update_settings(bells_data.clone(), |settings| settings.schedule.bell_schedules = parse_bell_schedules(&value));
+    };
+
+    let today = today();
+    let today_type = schedule.day_types.get(&today).cloned();
+    let day_type_data = data.clone();
+    let on_day_type = move |event: Event| {
+        let value = event.target().unwrap().dyn_into::<HtmlSelectElement>().unwrap().value();
+        update_settings(day_type_data.clone(), |settings| match value.as_str() {
+            "" => {
+                settings.schedule.day_types.remove(&today);
+            },
+            name => {
+                settings.schedule.day_types.insert(today, name.to_owned());
+            },
+        });
+    };
+
+    html! {
+        <div class="relative">
+            <button class={WIDGET_BUTTON} onclick={toggle}>
+                {match today_letter {
+                    Some(letter) => format!("{} day", letter),
+                    None => "No school".to_string(),
+                }}
+            </button>
+            {if *expanded {
+                html! {
+                    <div class={DROPDOWN}>
+                        <label class="flex flex-col gap-1">
+                            {"Rotation"}
+                            <input
+                                class={FIELD}
+                                value={schedule.rotation.iter().map(LetterDay::letter).collect::<String>()}
+                                onchange={on_rotation} />
+                        </label>
+                        <label class="flex flex-col gap-1">
+                            {"First day of the rotation"}
+                            <input
+                                class={FIELD}
+                                type="date"
+                                value={schedule.start_date.map(|date| date.format(DATE_FORMAT).to_string()).unwrap_or_default()}
+                                onchange={on_start} />
+                            <span class="text-xs text-gray-400">{"Leave empty to treat Monday as A day."}</span>
+                        </label>
+                        <label class="flex flex-col gap-1">
+                            {"Holidays (rotation pauses)"}
+                            <textarea
+                                class={build_classes!(FIELD, "h-20")}
+                                placeholder="YYYY-MM-DD, one per line"
+                                value={format_dates(&schedule.holidays)}
+                                onchange={on_holidays} />
+                        </label>
+                        <label class="flex flex-col gap-1">
+                            {"Skipped days (letter is lost)"}
+                            <textarea
+                                class={build_classes!(FIELD, "h-20")}
+                                placeholder="YYYY-MM-DD, one per line"
+                                value={format_dates(&schedule.skipped_days)}
+                                onchange={on_skipped} />
+                        </label>
+                        <label class="flex flex-col gap-1">
+                            {"Bell schedules"}
+                            <textarea
+                                class={build_classes!(FIELD, "h-40 font-mono")}
+                                placeholder={"[Regular]\n1 08:00-08:50\n2 08:55-09:45"}
+                                value={format_bell_schedules(&schedule.bell_schedules)}
+                                onchange={on_bells} />
+                            <span class="text-xs text-gray-400">{"The first schedule is used unless a day says otherwise."}</span>
+                        </label>
+                        <label class="flex flex-col gap-1">
+                            {"Today runs on"}
+                            <select class={FIELD} onchange={on_day_type}>
+                                <option value="" selected={today_type.is_none()}>{"Default"}</option>
+                                {schedule.bell_schedules
+                                    .iter()
+                                    .skip(1)
+                                    .map(|bell_schedule| html! {
+                                        <option
+                                            value={bell_schedule.name.clone()}
+                                            selected={today_type.as_ref() == Some(&bell_schedule.name)}>
+                                            {&bell_schedule.name}
+                                        </option>
+                                    })
+                                    .collect::<Html>()}
+                            </select>
+                        </label>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,379 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex}};
+
+use bbs_shared::{accounts::{AccountID, AccountList, AccountSummary, ChildID}, cache::BackendCache, errors::AccountError};
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+
+use zeroize::Zeroize;
+
+use crate::{requests::LoginChallenge, secret::SecretString, session::{PersistentCookies, Secret, SessionStore}, structs::AugClient, Credentials};
+
+/// Everything that belongs to one signed in schoology user.
+#[derive(Debug)]
+pub struct Account {
+    pub id: AccountID,
+    pub client: AugClient,
+    pub creds: Credentials,
+    /// The jar `client` sends from, along with its saved copy.
+    cookie_provider: Arc<PersistentCookies>,
+    store: Arc<dyn SessionStore>,
+    /// Keyed by child for parent accounts, so one child's grades never show up under another.
+    caches: Mutex<HashMap<Option<ChildID>, BackendCache>>,
+    active_child: Mutex<Option<ChildID>>,
+    /// Bumped when a child switch starts and when it lands, see `child_epoch`.
+    child_epoch: AtomicU64,
+    /// Set while Schoology waits for a verification code.
+    pending_challenge: Mutex<Option<LoginChallenge>>,
+}
+
+impl Account {
+    fn open(id: AccountID, store: Arc<dyn SessionStore>) -> Self {
+        let cookie_provider = Arc::new(PersistentCookies::load(id.clone(), store.clone()));
+        let cookies = cookie_provider.jar();
+
+        let client = Client::builder().cookie_provider(cookie_provider.clone()).build().unwrap();
+
+        Self {
+            id,
+            client: AugClient {
+                client,
+                cookies,
+            },
+            creds: Credentials::default(),
+            cookie_provider,
+            store,
+            caches: Mutex::new(HashMap::new()),
+            active_child: Mutex::new(None),
+            child_epoch: AtomicU64::new(0),
+            pending_challenge: Mutex::new(None),
+        }
+    }
+
+    /// The cache for whichever child is selected, or the account's own for students.
+    pub fn cache(&self) -> BackendCache {
+        let child = self.active_child();
+
+        match self.caches.lock() {
+            Ok(mut caches) => caches.entry(child).or_default().clone(),
+            Err(e) => {
+                eprintln!("Cache lock poisoned: {:#?}", e);
+                BackendCache::default()
+            },
+        }
+    }
+
+    /// Taken before a request whose answer depends on the selected child. Schoology switches children on its
+    /// side, so a response that arrives after a switch started could belong to either one and mustn't be cached.
+    pub fn child_epoch(&self) -> u64 {
+        self.child_epoch.load(Ordering::SeqCst)
+    }
+
+    pub fn is_current_child(&self, epoch: u64) -> bool {
+        self.child_epoch() == epoch
+    }
+
+    pub fn begin_child_switch(&self) {
+        self.child_epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn active_child(&self) -> Option<ChildID> {
+        self.active_child
+            .lock()
+            .map(|child| *child)
+            .unwrap_or(None)
+    }
+
+    /// Saves the current credentials so `login` can use them once the cookies stop working.
+    fn remember_credentials(&self) -> Result<(), AccountError> {
+        // the password is dropped once a login goes through, so it can only be saved when it's entered
+        if !self.creds.is_set() {
+            return match self.saved_credentials() {
+                Some(_) => Ok(()),
+                None => Err(AccountError::NoCredentials),
+            };
+        }
+
+        let password = self.creds.password();
+        let mut serialized = bincode::serialize(&(self.creds.username(), password.expose()))
+            .or(Err(AccountError::SerializationError))?;
+        let encoded = SecretString::new(base64::encode(&serialized));
+        serialized.zeroize();
+
+        self.store.save(&self.id, Secret::Credentials, encoded.expose())
+    }
+
+    /// Only there when the user ticked "remember password".
+    fn saved_credentials(&self) -> Option<(String, SecretString)> {
+        let encoded = SecretString::new(self.store.load(&self.id, Secret::Credentials)?);
+        let mut serialized = base64::decode(encoded.expose()).ok()?;
+        let saved = bincode::deserialize::<(String, String)>(&serialized);
+        serialized.zeroize();
+
+        match saved {
+            Ok((username, password)) => Some((username, SecretString::new(password))),
+            Err(_) => {
+                eprintln!("Saved credentials for {} were unreadable", self.id.0);
+                None
+            },
+        }
+    }
+
+    /// What was just typed in, or failing that whatever was remembered.
+    pub fn login_credentials(&self) -> Option<(String, SecretString)> {
+        if self.creds.is_set() {
+            Some((self.creds.username(), self.creds.password()))
+        } else {
+            self.saved_credentials()
+        }
+    }
+
+    pub fn can_sign_in(&self) -> bool {
+        self.creds.is_set() || self.saved_credentials().is_some()
+    }
+
+    fn forget_credentials(&self) -> Result<(), AccountError> {
+        self.store.delete(&self.id, Secret::Credentials)
+    }
+
+    /// Forgets the session: cookies in memory and in the session store, credentials, and every child's cache.
+    /// Every step runs even if an earlier one fails, so a stuck keyring can't leave the cookies in memory.
+    /// The first failure is the one returned.
+    pub fn clear_session(&self) -> Result<(), AccountError> {
+        let cookies = self.cookie_provider.clear();
+
+        self.creds.clear();
+        let caches = self.caches
+            .lock()
+            .map(|mut caches| caches.clear())
+            .or(Err(AccountError::LockError));
+        self.set_active_child(None);
+        self.set_challenge(None);
+
+        cookies.and(caches)
+    }
+
+    pub fn set_challenge(&self, challenge: Option<LoginChallenge>) {
+        match self.pending_challenge.lock() {
+            Ok(mut pending) => *pending = challenge,
+            Err(e) => eprintln!("Failed to get lock on login challenge: {:#?}", e),
+        }
+    }
+
+    pub fn take_challenge(&self) -> Option<LoginChallenge> {
+        self.pending_challenge.lock().ok()?.take()
+    }
+
+    pub fn set_active_child(&self, child: Option<ChildID>) {
+        match self.active_child.lock() {
+            Ok(mut active_child) => *active_child = child,
+            Err(e) => eprintln!("Failed to get lock on active child: {:#?}", e),
+        }
+        self.child_epoch.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// What gets written to `accounts.json`; cookies stay in the session store.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AccountIndex {
+    accounts: Vec<AccountSummary>,
+    active: Option<AccountID>,
+}
+
+/// Every account on this computer, with one of them active. Commands act on whichever is active,
+/// so switching accounts is just swapping which `Account` that is.
+#[derive(Debug)]
+pub struct Accounts {
+    path: Option<PathBuf>,
+    store: Arc<dyn SessionStore>,
+    index: Mutex<AccountIndex>,
+    open: Mutex<Vec<Arc<Account>>>,
+    active: Mutex<Arc<Account>>,
+}
+
+impl Accounts {
+    pub fn load(path: Option<PathBuf>, store: Arc<dyn SessionStore>) -> Self {
+        let mut index = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| match serde_json::from_str::<AccountIndex>(&json) {
+                Ok(index) => Some(index),
+                Err(e) => {
+                    eprintln!("Failed to parse accounts, starting fresh: {}", e);
+                    None
+                },
+            })
+            .unwrap_or_default();
+
+        // picks up the cookie jar saved before there was more than one account
+        if index.accounts.is_empty() {
+            index.accounts.push(AccountSummary {
+                id: AccountID("default".to_owned()),
+                username: String::new(),
+                remember_password: false,
+            });
+        }
+
+        let active_id = index
+            .active
+            .clone()
+            .filter(|id| index.accounts.iter().any(|account| &account.id == id))
+            .unwrap_or_else(|| index.accounts[0].id.clone());
+        index.active = Some(active_id.clone());
+
+        let active = Arc::new(Account::open(active_id, store.clone()));
+
+        Self {
+            path,
+            store,
+            index: Mutex::new(index),
+            open: Mutex::new(vec![active.clone()]),
+            active: Mutex::new(active),
+        }
+    }
+
+    pub fn active(&self) -> Arc<Account> {
+        self.active
+            .lock()
+            .map(|active| active.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    pub fn list(&self) -> Result<AccountList, AccountError> {
+        let index = self.index.lock().or(Err(AccountError::LockError))?;
+
+        Ok(AccountList {
+            accounts: index.accounts.clone(),
+            active: index.active.clone(),
+        })
+    }
+
+    /// Makes `id` the active account, reusing its client and cache if it was opened earlier this session.
+    pub fn switch(&self, id: &AccountID) -> Result<(), AccountError> {
+        {
+            let mut index = self.index.lock().or(Err(AccountError::LockError))?;
+            if !index.accounts.iter().any(|account| &account.id == id) {
+                return Err(AccountError::UnknownAccount);
+            }
+            index.active = Some(id.clone());
+        }
+
+        let account = {
+            let mut open = self.open.lock().or(Err(AccountError::LockError))?;
+            match open.iter().find(|account| &account.id == id) {
+                Some(account) => account.clone(),
+                None => {
+                    let account = Arc::new(Account::open(id.clone(), self.store.clone()));
+                    open.push(account.clone());
+                    account
+                },
+            }
+        };
+
+        *self.active.lock().or(Err(AccountError::LockError))? = account;
+        self.save()
+    }
+
+    /// Creates a blank account and switches to it, ready for the login page to fill in.
+    pub fn add(&self) -> Result<AccountID, AccountError> {
+        let id = {
+            let mut index = self.index.lock().or(Err(AccountError::LockError))?;
+            let id = (1..)
+                .map(|n| AccountID(format!("account-{}", n)))
+                .find(|id| !index.accounts.iter().any(|account| &account.id == id))
+                .unwrap();
+            index.accounts.push(AccountSummary {
+                id: id.clone(),
+                username: String::new(),
+                remember_password: false,
+            });
+            id
+        };
+
+        self.switch(&id)?;
+        Ok(id)
+    }
+
+    /// Stores or deletes the active account's password, and remembers the choice for the login page.
+    pub fn set_remember_password(&self, remember: bool) -> Result<(), AccountError> {
+        let active = self.active();
+
+        if remember {
+            active.remember_credentials()?;
+        } else {
+            active.forget_credentials()?;
+        }
+
+        self.record_remember_password(&active.id, remember)
+    }
+
+    /// Whether the active account is set to keep its password.
+    pub fn remembers_password(&self) -> bool {
+        let active_id = self.active().id.clone();
+
+        self.index
+            .lock()
+            .ok()
+            .and_then(|index| index.accounts.iter().find(|account| account.id == active_id).map(|account| account.remember_password))
+            .unwrap_or(false)
+    }
+
+    /// Takes the choice from the login page. Turning it on waits for `save_remembered_password`, so a mistyped
+    /// password never replaces one that worked; turning it off deletes the saved one straight away.
+    pub fn choose_remember_password(&self, remember: bool) -> Result<(), AccountError> {
+        if remember {
+            self.record_remember_password(&self.active().id, true)
+        } else if self.remembers_password() {
+            self.set_remember_password(false)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Stores the password that just signed in, if the active account is set to keep it.
+    pub fn save_remembered_password(&self) -> Result<(), AccountError> {
+        if self.remembers_password() {
+            self.active().remember_credentials()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn record_remember_password(&self, id: &AccountID, remember: bool) -> Result<(), AccountError> {
+        {
+            let mut index = self.index.lock().or(Err(AccountError::LockError))?;
+            if let Some(account) = index.accounts.iter_mut().find(|account| &account.id == id) {
+                account.remember_password = remember;
+            }
+        }
+        self.save()
+    }
+
+    /// Records who the active account belongs to once credentials are entered for it.
+    pub fn set_active_username(&self, username: &str) -> Result<(), AccountError> {
+        let active_id = self.active().id.clone();
+        {
+            let mut index = self.index.lock().or(Err(AccountError::LockError))?;
+            if let Some(account) = index.accounts.iter_mut().find(|account| account.id == active_id) {
+                account.username = username.to_owned();
+            }
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), AccountError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let json = {
+            let index = self.index.lock().or(Err(AccountError::LockError))?;
+            serde_json::to_string_pretty(&*index).or(Err(AccountError::SerializationError))?
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).or(Err(AccountError::WriteError))?;
+        }
+        fs::write(path, json).or(Err(AccountError::WriteError))
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashSet, error::Error, sync::{Arc, Mutex}};
+
+use bbs_shared::{accounts::AccountID, events::Event, SectionID};
+use tauri::{http::{Request as HttpRequest, Response as HttpResponse, ResponseBuilder}, AppHandle, Manager, Runtime};
+use url::{form_urlencoded, Url};
+
+use crate::{accounts::{Account, Accounts}, disk_cache::DiskCache, requests::get_image};
+
+pub const ASSET_SCHEME: &str = "bbs";
+
+lazy_static::lazy_static! {
+    /// Source urls being downloaded, per account, so an image requested again before it lands doesn't start a second fetch.
+    static ref IN_FLIGHT: Mutex<HashSet<(AccountID, String)>> = Mutex::new(HashSet::new());
+}
+
+// webview2 can't load custom schemes directly, so tauri maps them onto `https://<scheme>.localhost`
+#[cfg(windows)]
+const ASSET_BASE: &str = "https://bbs.localhost/asset/";
+
+#[cfg(not(windows))]
+const ASSET_BASE: &str = "bbs://localhost/asset/";
+
+pub fn thumbnail_url(section: SectionID) -> String {
+    format!("{}thumb/{}", ASSET_BASE, section.0)
+}
+
+/// Proxies an arbitrary schoology url (inline body images, attachment previews, avatars) through the logged in client.
+pub fn remote_url(url: &str) -> String {
+    format!("{}remote/{}", ASSET_BASE, form_urlencoded::byte_serialize(url.as_bytes()).collect::<String>())
+}
+
+/// Only schoology hosts are proxied, so a crafted url can't turn the client into an open relay.
+fn is_allowed_remote(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "https")
+        .and_then(|url| url.host_str().map(|host| host == "schoology.com" || host.ends_with(".schoology.com")))
+        .unwrap_or(false)
+}
+
+/// Only formats the webview shows as images. Anything else (usually Schoology's login page, once the session
+/// has run out) is refused rather than cached and served under an image url.
+fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ if is_svg(bytes) => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+fn is_svg(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+    let head = head.trim_start();
+    (head.starts_with("<svg") || head.starts_with("<?xml")) && head.contains("<svg") && !head.contains("<html")
+}
+
+/// Where on Schoology an asset path points.
+fn source_url<R: Runtime>(app: &AppHandle<R>, path: &str) -> Option<String> {
+    match path.split_once('/')? {
+        ("thumb", section) => {
+            let section = SectionID(section.parse().ok()?);
+            let url = app
+                .state::<Accounts>()
+                .active()
+                .cache()
+                .thumbnail_urls
+                .lock()
+                .ok()?
+                .get(&section)?
+                .clone();
+            Some(url)
+        },
+        ("remote", encoded) => {
+            let url: String = form_urlencoded::parse(format!("url={}", encoded).as_bytes())
+                .next()?
+                .1
+                .into_owned();
+            is_allowed_remote(&url).then(|| url)
+        },
+        _ => None,
+    }
+}
+
+/// Protocol handlers run on the main thread, so downloads happen in the background. Once the image is on
+/// disk the frontend hears about it through `Event::AssetReady` and asks again.
+fn start_fetch<R: Runtime>(app: &AppHandle<R>, account: Arc<Account>, url: String, asset: String) {
+    match IN_FLIGHT.lock() {
+        Ok(mut in_flight) => if !in_flight.insert((account.id.clone(), url.clone())) {
+            return;
+        },
+        Err(e) => return eprintln!("Failed to get lock on asset downloads: {}", e),
+    }
+
+    tauri::async_runtime::spawn(fetch(app.clone(), account, url, asset));
+}
+
+async fn fetch<R: Runtime>(app: AppHandle<R>, account: Arc<Account>, url: String, asset: String) {
+    let fetched = async {
+        let response = get_image(&account.client.client, &url).await.ok()?;
+        response.bytes().await.ok()
+    }.await;
+
+    match fetched {
+        Some(bytes) if sniff_mime(&bytes).is_some() => {
+            app.state::<DiskCache>().for_account(&account.id).put(&url, &bytes);
+
+            match Event::AssetReady(asset).to_base64() {
+                Ok(payload) => if let Err(e) = app.emit_all("bbs-event", payload) {
+                    eprintln!("Failed to emit asset ready: {}", e);
+                },
+                Err(e) => eprintln!("Failed to serialize asset ready: {}", e),
+            }
+        },
+        Some(_) => eprintln!("Refusing to cache {}, it isn't an image", url),
+        None => eprintln!("Failed to download {}", url),
+    }
+
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        in_flight.remove(&(account.id.clone(), url));
+    }
+}
+
+pub fn handle_asset_request<R: Runtime>(app: &AppHandle<R>, request: &HttpRequest) -> Result<HttpResponse, Box<dyn Error>> {
+    let path = Url::parse(request.uri())?
+        .path()
+        .trim_start_matches('/')
+        .strip_prefix("asset/")
+        .map(ToOwned::to_owned);
+
+    let (path, url) = match path.and_then(|path| Some((source_url(app, &path)?, path))) {
+        Some((url, path)) => (path, url),
+        None => return ResponseBuilder::new().status(404).body(Vec::new()),
+    };
+
+    let account = app.state::<Accounts>().active();
+    let cached = app
+        .state::<DiskCache>()
+        .for_account(&account.id)
+        .get(&url)
+        .and_then(|bytes| Some((sniff_mime(&bytes)?, bytes)));
+
+    match cached {
+        Some((mime, bytes)) => ResponseBuilder::new()
+            .status(200)
+            .mimetype(mime)
+            .header("Cache-Control", "max-age=86400")
+            .body(bytes),
+        None => {
+            start_fetch(app, account, url, format!("{}{}", ASSET_BASE, path));
+
+            // not cached, so the webview mustn't remember the miss either
+            ResponseBuilder::new()
+                .status(404)
+                .header("Cache-Control", "no-store")
+                .body(Vec::new())
+        },
+    }
+}
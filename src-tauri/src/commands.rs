@@ -1,62 +1,224 @@
-use std::{collections::HashMap, sync::Arc, time::SystemTime};
+use std::{collections::HashMap, fmt::Display, fs, path::PathBuf, sync::{Arc, Mutex}, time::SystemTime};
 
-use bbs_shared::{ data::{ClassEntry, Assignment, AssignmentType}, ClassID, cache::{BackendCache, CacheDataState}, SectionID, errors::{CredSetError, LoginError}, MaterialID };
-use keyring::Entry;
-use tauri::State;
-use reqwest::{Method};
-use scraper::{Html, Selector};
+use bbs_shared::{ data::{ClassEntry, Assignment, AssignmentType, FeedItem, Instructor, Member, MemberPage, MemberRole, Submission, SubmissionState, SubmittedFile, UpcomingItem}, grades::{GradeReport, GradingPeriod, GradeCategory, GradedItem, Grade}, ClassID, accounts::{AccountID, Child, ChildID, ParentInfo}, cache::{CacheDataState, TimedComponent}, SectionID, errors::{AccountError, CredSetError, LoginError, MessageError, SettingsError}, events::{Event, DataType, UploadProgress}, settings::Settings, messages::{Message, MessageThread, NewMessage, ThreadID, ThreadSummary}, notifications::{Notification, NotificationID, NotificationKind}, MaterialID };
+use futures_util::{stream, StreamExt};
+use tauri::{AppHandle, State, Window};
+use reqwest::{Client, Method, Response};
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
 
-use crate::{requests::{get_login_page, login, make_api_request, get_single_class, get_material_info, get_class_discussions}, Credentials, structs::{ActiveClasses, AugClient}};
+/// How many material pages a class load fetches at once.
+const MATERIAL_FETCH_LIMIT: usize = 6;
+
+/// For `map_err`: encodes the `LoginError` variant along with why the step failed.
+fn login_error<E: Display>(variant: fn(String) -> LoginError) -> impl Fn(E) -> String {
+    move |e| variant(e.to_string()).into()
+}
+
+use crate::{requests::{get_login_page, login, make_api_request, get_single_class, get_material_info, get_class_discussions, get_home_feed_page, get_upcoming_page, get_grades_page, get_assignment_page, submit_dropbox, get_members_page, get_user_info_page, get_notifications_page, get_home_page, select_child, logout_remote, get_inbox_page, get_thread_page, send_new_message, send_reply, send_login_code, LoginPage, SCHOOLOGY_BASE}, accounts::Accounts, secret::SecretString, sso::open_sso_window, disk_cache::DiskCache, settings::SettingsStore, assets::{thumbnail_url, remote_url}, structs::{ActiveClasses, HomeFeedPage, UpcomingPage}};
 
 #[tauri::command]
-pub async fn set_credentials(creds: State<'_, Credentials>, username: String, password: String) -> Result<(), String> {
-    match (creds.username.lock(), creds.password.lock()) {
-        (
-            Ok(mut username_lock),
-            Ok(mut password_lock)
-        ) => {
-            username_lock.clone_from(&Arc::new(username));
-            password_lock.clone_from(&Arc::new(password));
-            return Ok(());
+pub async fn set_credentials(accounts: State<'_, Accounts>, username: String, password: String, remember: bool) -> Result<(), String> {
+    let account = accounts.active();
+
+    if let Err(e) = accounts.set_active_username(&username) {
+        eprintln!("Failed to save account: {}", e);
+    }
+
+    account.creds
+        .set(username, SecretString::new(password))
+        .map_err(Into::<String>::into)?;
+
+    accounts.choose_remember_password(remember).map_err(Into::into)
+}
+
+/// Called once a login accepts the password, before it's dropped from memory.
+fn remember_password(accounts: &Accounts) {
+    if let Err(e) = accounts.save_remembered_password() {
+        eprintln!("Failed to remember password: {}", e);
+    }
+}
+
+/// Finishes a login that stopped at a verification code. The frontend fetches the listing afterwards as usual.
+#[tauri::command]
+pub async fn submit_login_code(accounts: State<'_, Accounts>, code: String) -> Result<(), String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let challenge = account.take_challenge().ok_or::<String>(CodeExpired.into())?;
+
+    match send_login_code(&account.client.client, &challenge, code.trim()).await {
+        Ok(None) => Ok(()),
+        Ok(Some(next)) => {
+            let hint = next.hint.clone();
+            account.set_challenge(Some(next));
+            Err(InvalidCode { hint }.into())
+        },
+        Err(CodeExpired) => Err(CodeExpired.into()),
+        Err(e) => {
+            // the code may still be good once whatever went wrong clears up
+            account.set_challenge(Some(challenge));
+            Err(e.into())
         },
+    }
+}
+
+/// Drops the half-finished login so the next attempt starts over with the password.
+#[tauri::command]
+pub async fn cancel_login_code(accounts: State<'_, Accounts>) -> Result<(), String> {
+    accounts.active().set_challenge(None);
+    Ok(())
+}
 
-        (
-            Err(username_error),
-            Ok(_)
-        ) => eprintln!("Failed to get lock on username: {:#?}", username_error),
+#[tauri::command]
+pub async fn start_sso(app: AppHandle) -> Result<(), String> {
+    open_sso_window(&app).map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_remember_password(accounts: State<'_, Accounts>, remember: bool) -> Result<(), String> {
+    accounts.set_remember_password(remember).map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn list_accounts(accounts: State<'_, Accounts>) -> Result<String, String> {
+    use bbs_shared::errors::AccountError::*;
+
+    accounts
+        .list()
+        .map_err(Into::<String>::into)?
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
+}
+
+#[tauri::command]
+pub async fn switch_account(accounts: State<'_, Accounts>, accountid: String) -> Result<(), String> {
+    accounts.switch(&AccountID(accountid)).map_err(Into::into)
+}
+
+/// Adds a blank account and makes it active; the frontend sends the user to the login page next.
+#[tauri::command]
+pub async fn add_account(accounts: State<'_, Accounts>) -> Result<(), String> {
+    accounts.add().map(|_| ()).map_err(Into::into)
+}
+
+/// Tears down the active account's session. With `everywhere`, Schoology is told to end it as well,
+/// which has to happen first since it needs the cookies that get thrown away after. Every step runs
+/// regardless of the others; anything that failed is logged and the first failure is returned.
+#[tauri::command]
+pub async fn logout(
+    accounts: State<'_, Accounts>,
+    disk_cache: State<'_, DiskCache>,
+    everywhere: bool,
+) -> Result<(), String> {
+    use bbs_shared::errors::AccountError::*;
+
+    let account = accounts.active();
+
+    let remote_result = if everywhere {
+        logout_remote(&account.client.client).await.map(|_| ()).or(Err(RequestError))
+    } else {
+        Ok(())
+    };
+
+    let forget_result = accounts.set_remember_password(false);
+    let clear_result = account.clear_session();
+    disk_cache.for_account(&account.id).clear();
+
+    let errors: Vec<AccountError> = [remote_result, forget_result, clear_result]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+    errors.iter().for_each(|error| eprintln!("Logout step failed: {}", error));
+
+    match errors.first() {
+        Some(error) => Err((*error).into()),
+        None => Ok(()),
+    }
+}
+
+/// Lists the children of a parent account; students get an empty `ParentInfo`.
+#[tauri::command]
+pub async fn get_children(accounts: State<'_, Accounts>) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+
+    let home_text = get_home_page(&account.client.client)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
 
-        (
-            Ok(_),
-            Err(password_error)
-        ) => eprintln!("Failed to get lock on password: {:#?}", password_error),
+    let mut parent_info = children_data(Html::parse_document(&home_text));
 
-        (
-            Err(username_error),
-            Err(password_error)
-        ) => eprintln!("Failed to get lock on username and password: {:#?} {:#?}", username_error, password_error),
+    // the page knows who is selected even when this session hasn't switched yet
+    match parent_info.active {
+        Some(child) => account.set_active_child(Some(child)),
+        None => parent_info.active = account.active_child(),
     }
 
-    Err(CredSetError.into())
+    parent_info
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
 }
 
+#[tauri::command]
+pub async fn switch_child(accounts: State<'_, Accounts>, childid: String) -> Result<(), String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let child = ChildID(u64::from_str_radix(&childid, 10).map_err(login_error(DecodeError))?);
+    let account = accounts.active();
+    account.begin_child_switch();
+
+    select_child(&account.client.client, child)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .error_for_status()
+        .map_err(login_error(LaterRequestError))?;
+
+    account.set_active_child(Some(child));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_settings(accounts: State<'_, Accounts>, store: State<'_, SettingsStore>) -> Result<String, String> {
+    store
+        .get(&accounts.active().id)
+        .to_base64()
+        .or::<String>(Err(SettingsError::DecodeError.into()))
+}
+
+#[tauri::command]
+pub async fn set_settings(accounts: State<'_, Accounts>, store: State<'_, SettingsStore>, settings: String) -> Result<(), String> {
+    let settings = Settings::from_base64(settings.as_bytes())
+        .or::<String>(Err(SettingsError::DecodeError.into()))?;
+
+    store.set(&accounts.active().id, settings).map_err(Into::into)
+}
 
+
+#[tauri::command]
 pub async fn is_logged_in(
-    aug_client: State<'_, AugClient>,
-    cache: State<'_, BackendCache>,
+    accounts: State<'_, Accounts>,
 ) -> Result<String, String> {
     use bbs_shared::errors::LoginError::SerializationError;
 
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let cache = account.cache();
+
     let client = &aug_client.client;
 
     let return_bool = if cache.get_class_listing_state() == CacheDataState::Ok {
         Ok(true)
     } else {
         match get_login_page(client).await {
-            Ok(_) => Ok(false),
-            Err(e) => match e {
-                LoginError::FindFormError => Ok(true), // TODO: Handle case where schoology is down.
-                _ => Err::<_, String>(e.into()),
-            },
+            Ok(LoginPage::SignedIn) => Ok(true),
+            // a remembered password lets `get_class_listing` sign straight back in
+            Ok(LoginPage::Form(_)) => Ok(account.can_sign_in()),
+            Err(e) => Err::<_, String>(e.into()),
         }
     }?;
 
@@ -70,16 +232,16 @@ pub async fn is_logged_in(
 
 #[tauri::command]
 pub async fn get_class_listing(
-    aug_client: State<'_, AugClient>,
-    creds: State<'_, Credentials>,
-    cache: State<'_, BackendCache>,
-    keyring_entry: State<'_, Option<Entry>>,
+    accounts: State<'_, Accounts>,
 ) -> Result<String, String> {
-    
     use bbs_shared::errors::LoginError::*;
 
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let epoch = account.child_epoch();
+    let cache = account.cache();
+
     let client = &aug_client.client;
-    let cookie_jar = &aug_client.cookies;
 
     if cache.get_class_listing_state() == CacheDataState::Ok {
         if let Some(guard) = cache.class_listing.data.try_lock().ok() {
@@ -94,19 +256,31 @@ pub async fn get_class_listing(
     }
     
     match get_login_page(client).await {
-        Ok(login_form_details) => {
+        Ok(LoginPage::Form(login_form)) => {
+            let (username, password) = account.login_credentials().unwrap_or_default();
+
             match login(
                 client,
-                creds,
-                cookie_jar,
-                keyring_entry,
-                login_form_details,
+                &username,
+                &password,
+                login_form,
             ).await {
-                Ok(_) => (),
+                Ok(None) => {
+                    remember_password(&accounts);
+                    account.creds.clear_password();
+                },
+                Ok(Some(challenge)) => {
+                    // the password got through, only the code is left
+                    remember_password(&accounts);
+                    account.creds.clear_password();
+                    let hint = challenge.hint.clone();
+                    account.set_challenge(Some(challenge));
+                    return Err(CodeRequired { hint }.into());
+                },
                 Err(e) => return Err(e.into())
             }
         },
-        Err(FindFormError) => (),
+        Ok(LoginPage::SignedIn) => (),
         Err(e) => return Err(e.into()),
     };
 
@@ -117,10 +291,10 @@ pub async fn get_class_listing(
         &HashMap::<(), ()>::new(),
     )
         .await
-        .or::<String>(Err(LaterRequestError.into()))?
+        .map_err(login_error(LaterRequestError))?
         .text()
         .await
-        .or::<String>(Err(DecodeError.into()))?;
+        .map_err(login_error(DecodeError))?;
 
     
     let active: ActiveClasses = serde_json
@@ -129,6 +303,24 @@ pub async fn get_class_listing(
 
     let course_listing = active.body.courses.to_by_id();
 
+    // nothing from here on is cached if a child switch got in the way
+    let current = account.is_current_child(epoch);
+
+    if current {
+        match cache.thumbnail_urls.lock() {
+            Ok(mut thumbnail_urls) => thumbnail_urls.extend(
+                course_listing
+                    .data
+                    .values()
+                    .filter_map(|(_, section)| Some((
+                        SectionID(section.nid),
+                        section.logo_img_src.card_thumbnail_2x.clone().or_else(|| section.logo_img_src.card_thumbnail.clone())?,
+                    )))
+            ),
+            Err(e) => eprintln!("Cache lock poisoned: {:#?}", e),
+        }
+    }
+
     let mut courses: Vec<_> = course_listing
         .data
         .iter()
@@ -137,15 +329,22 @@ pub async fn get_class_listing(
             section: section.section_title.as_str().into(),
             id: ClassID(*nid),
             section_nid: SectionID(section.nid),
-            picture: Vec::new(),
-            instructors: None,
+            instructors: cache
+                .instructors
+                .lock()
+                .ok()
+                .and_then(|instructors| instructors.get(&SectionID(section.nid)).cloned()),
+            picture: section.logo_img_src.card_thumbnail_2x
+                .as_ref()
+                .or(section.logo_img_src.card_thumbnail.as_ref())
+                .map(|_| thumbnail_url(SectionID(section.nid))),
         })
         .chain([ClassEntry {
             name: "Test Class with Bad Section".into(),
             section: "P(A-D,E)".into(),
             id: ClassID(123456),
             section_nid: SectionID(654321),
-            picture: Vec::new(),
+            picture: None,
             instructors: None
         }].into_iter())
         .collect();
@@ -158,20 +357,501 @@ pub async fn get_class_listing(
             .or::<String>(Err(SerializationError.into()))?,
     );
 
-    match (cache.class_listing.prev_update.lock(), cache.class_listing.data.lock()) {
-        (Ok(mut prev_update), Ok(mut class_listing)) => {
-            *prev_update = SystemTime::now();
-            *class_listing = Some(courses);
+    if current {
+        match (cache.class_listing.prev_update.lock(), cache.class_listing.data.lock()) {
+            (Ok(mut prev_update), Ok(mut class_listing)) => {
+                *prev_update = SystemTime::now();
+                *class_listing = Some(courses);
+            },
+            (
+                update_res,
+                data_res,
+            ) => eprintln!("Cache lock poisoned: {:#?}\n{:#?}", update_res, data_res),
+        }
+    }
+
+    Ok(encoded_output)
+}
+
+#[tauri::command]
+pub async fn get_submission(
+    accounts: State<'_, Accounts>,
+    materialid: String,
+) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let id = MaterialID(u64::from_str_radix(&materialid, 10).map_err(login_error(DecodeError))?);
+
+    let assignment_text = get_assignment_page(&aug_client.client, SCHOOLOGY_BASE, id)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    submission_data(&Html::parse_document(&assignment_text))
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
+}
+
+#[tauri::command]
+pub async fn submit_assignment(
+    window: Window,
+    accounts: State<'_, Accounts>,
+    materialid: String,
+    upload: u64,
+    paths: Vec<String>,
+) -> Result<String, String> {
+    use bbs_shared::errors::UploadError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let id = MaterialID(u64::from_str_radix(&materialid, 10).or::<String>(Err(SerializationError.into()))?);
+
+    let files = paths
+        .iter()
+        .map(PathBuf::from)
+        .map(|path| {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            fs::read(&path).map(|bytes| (file_name, bytes))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .or::<String>(Err(ReadFileError.into()))?;
+
+    let on_progress = Arc::new(move |progress: UploadProgress| {
+        match Event::UploadProgress(progress).to_base64() {
+            Ok(payload) => if let Err(e) = window.emit("bbs-event", payload) {
+                eprintln!("Failed to emit upload progress: {}", e);
+            },
+            Err(e) => eprintln!("Failed to serialize upload progress: {}", e),
+        }
+    });
+
+    let assignment_text = submit_dropbox(&aug_client.client, SCHOOLOGY_BASE, upload, id, files, on_progress)
+        .await
+        .map_err(Into::<String>::into)?;
+
+    submission_data(&Html::parse_document(&assignment_text))
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
+}
+
+#[tauri::command]
+pub async fn get_home_feed(
+    accounts: State<'_, Accounts>,
+) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let epoch = account.child_epoch();
+    let cache = account.cache();
+
+    if cache.get_home_feed_state() == CacheDataState::Ok {
+        if let Some(guard) = cache.home_feed.data.try_lock().ok() {
+            if let Some(feed) = guard.as_ref() {
+                return Ok(base64::encode(
+                    bincode
+                        ::serialize(feed)
+                        .or::<String>(Err(SerializationError.into()))?,
+                ));
+            }
+        }
+    }
+
+    let feed_text = get_home_feed_page(&aug_client.client, 0)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    let feed_page: HomeFeedPage = serde_json
+        ::from_str(feed_text.as_ref())
+        .or::<String>(Err(JsonError.into()))?;
+
+    let feed = feed_data(Html::parse_fragment(&feed_page.output));
+
+    let encoded_output = base64::encode(
+        bincode
+            ::serialize(&feed)
+            .or::<String>(Err(SerializationError.into()))?,
+    );
+
+    if !account.is_current_child(epoch) {
+        return Ok(encoded_output);
+    }
+
+    match cache.home_feed.data.lock() {
+        Ok(mut home_feed) => {
+            cache.home_feed.touch();
+            *home_feed = Some(feed);
+        },
+        Err(e) => eprintln!("Cache lock poisoned: {:#?}", e),
+    }
+
+    Ok(encoded_output)
+}
+
+#[tauri::command]
+pub async fn get_upcoming(accounts: State<'_, Accounts>) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let upcoming_text = get_upcoming_page(&aug_client.client)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    let upcoming_page: UpcomingPage = serde_json
+        ::from_str(upcoming_text.as_ref())
+        .or::<String>(Err(JsonError.into()))?;
+
+    Ok(base64::encode(
+        bincode
+            ::serialize(&upcoming_data(Html::parse_fragment(&upcoming_page.html)))
+            .or::<String>(Err(SerializationError.into()))?,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_grades(
+    accounts: State<'_, Accounts>,
+) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let epoch = account.child_epoch();
+    let cache = account.cache();
+
+    if cache.get_grades_state() == CacheDataState::Ok {
+        if let Some(guard) = cache.grades.data.try_lock().ok() {
+            if let Some(grades) = guard.as_ref() {
+                return Ok(base64::encode(
+                    bincode
+                        ::serialize(grades)
+                        .or::<String>(Err(SerializationError.into()))?,
+                ));
+            }
+        }
+    }
+
+    let grades_text = get_grades_page(&aug_client.client)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    let grades = grades_data(Html::parse_document(&grades_text));
+
+    let encoded_output = base64::encode(
+        bincode
+            ::serialize(&grades)
+            .or::<String>(Err(SerializationError.into()))?,
+    );
+
+    if !account.is_current_child(epoch) {
+        return Ok(encoded_output);
+    }
+
+    match cache.grades.data.lock() {
+        Ok(mut cached_grades) => {
+            cache.grades.touch();
+            *cached_grades = Some(grades);
         },
-        (
-            update_res,
-            data_res,
-        ) => eprintln!("Cache lock poisoned: {:#?}\n{:#?}", update_res, data_res),
+        Err(e) => eprintln!("Cache lock poisoned: {:#?}", e),
     }
 
     Ok(encoded_output)
 }
 
+async fn fetch_instructors(client: &Client, section: SectionID) -> Option<Vec<Instructor>> {
+    let members_text = get_members_page(client, section, "", 1)
+        .await
+        .and_then(Response::error_for_status)
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+
+    // teachers are listed first, so the first page is enough
+    let mut instructors: Vec<_> = member_data(Html::parse_fragment(&members_text))
+        .members
+        .into_iter()
+        .filter(|member| member.role == MemberRole::Admin)
+        .map(|member| Instructor {
+            name: member.name,
+            email: None,
+            profile_url: member.profile_url,
+        })
+        .collect();
+
+    // emails only live on each profile's info tab; a failed lookup fails the whole fetch so a missing
+    // email doesn't get cached for good
+    for instructor in instructors.iter_mut() {
+        if let Some(profile_url) = &instructor.profile_url {
+            let info_text = get_user_info_page(client, profile_url)
+                .await
+                .and_then(Response::error_for_status)
+                .ok()?
+                .text()
+                .await
+                .ok()?;
+            instructor.email = email_data(&Html::parse_document(&info_text));
+        }
+    }
+
+    Some(instructors)
+}
+
+#[tauri::command]
+pub async fn get_class_members(
+    accounts: State<'_, Accounts>,
+    sectionid: String,
+    search: String,
+    page: u32,
+) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let cache = account.cache();
+
+    let section = SectionID(u64::from_str_radix(&sectionid, 10).map_err(login_error(DecodeError))?);
+    let search = search.trim();
+
+    if search.is_empty() {
+        if let Ok(member_pages) = cache.member_pages.lock() {
+            if let Some(member_page) = member_pages.get(&(section, page)).filter(|member_page| member_page.is_fresh()) {
+                return member_page.data
+                    .to_base64()
+                    .or::<String>(Err(SerializationError.into()));
+            }
+        }
+    }
+
+    let members_text = get_members_page(&aug_client.client, section, search, page)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    let member_page = member_data(Html::parse_fragment(&members_text));
+
+    let encoded_output = member_page
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))?;
+
+    if !search.is_empty() {
+        return Ok(encoded_output);
+    }
+
+    match cache.member_pages.lock() {
+        Ok(mut member_pages) => {
+            member_pages.insert((section, page), TimedComponent {
+                prev_update: Arc::new(Mutex::new(SystemTime::now())),
+                data: member_page,
+            });
+        },
+        Err(e) => eprintln!("Cache lock poisoned: {:#?}", e),
+    }
+
+    Ok(encoded_output)
+}
+
+/// Returns the cached instructors, or `None` while they're fetched in the background.
+/// A `SingleClassListingTeachers` event is emitted once they land in the cache.
+#[tauri::command]
+pub async fn get_class_instructors(
+    window: Window,
+    accounts: State<'_, Accounts>,
+    sectionid: String,
+) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+    let cache = account.cache();
+
+    let section = SectionID(u64::from_str_radix(&sectionid, 10).map_err(login_error(DecodeError))?);
+
+    let cached = cache
+        .instructors
+        .lock()
+        .ok()
+        .and_then(|instructors| instructors.get(&section).cloned());
+
+    let should_fetch = cached.is_none() && cache
+        .instructors_pending
+        .lock()
+        .map(|mut pending| pending.insert(section))
+        .unwrap_or(false);
+
+    if should_fetch {
+        let client = aug_client.client.clone();
+        let instructors_cache = cache.instructors.clone();
+        let instructors_pending = cache.instructors_pending.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let instructors = fetch_instructors(&client, section).await;
+
+            // nothing is cached on failure, so the next request tries again
+            let cached = match (instructors, instructors_cache.lock()) {
+                (Some(instructors), Ok(mut cached_instructors)) => {
+                    cached_instructors.insert(section, instructors);
+                    true
+                },
+                (None, _) => {
+                    eprintln!("Failed to fetch instructors for section {}", section.0);
+                    false
+                },
+                (_, Err(e)) => {
+                    eprintln!("Cache lock poisoned: {:#?}", e);
+                    false
+                },
+            };
+
+            if let Ok(mut pending) = instructors_pending.lock() {
+                pending.remove(&section);
+            }
+
+            if !cached {
+                return;
+            }
+
+            let event = Event::NewDataReady { data_type: DataType::SingleClassListingTeachers { id: section.0 } };
+            match event.to_base64() {
+                Ok(payload) => if let Err(e) = window.emit("bbs-event", payload) {
+                    eprintln!("Failed to emit instructors event: {}", e);
+                },
+                Err(e) => eprintln!("Failed to serialize instructors event: {}", e),
+            }
+        });
+    }
+
+    Ok(base64::encode(
+        bincode
+            ::serialize(&cached)
+            .or::<String>(Err(SerializationError.into()))?,
+    ))
+}
+#[tauri::command]
+pub async fn get_notifications(accounts: State<'_, Accounts>) -> Result<String, String> {
+    use bbs_shared::errors::LoginError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let notifications_text = get_notifications_page(&aug_client.client)
+        .await
+        .map_err(login_error(LaterRequestError))?
+        .text()
+        .await
+        .map_err(login_error(DecodeError))?;
+
+    Ok(base64::encode(
+        bincode
+            ::serialize(&notification_data(Html::parse_document(&notifications_text)))
+            .or::<String>(Err(SerializationError.into()))?,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_inbox(accounts: State<'_, Accounts>) -> Result<String, String> {
+    use bbs_shared::errors::MessageError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let inbox_text = get_inbox_page(&aug_client.client)
+        .await
+        .or::<String>(Err(RequestError.into()))?
+        .text()
+        .await
+        .or::<String>(Err(DecodeError.into()))?;
+
+    Ok(base64::encode(
+        bincode
+            ::serialize(&inbox_data(Html::parse_document(&inbox_text)))
+            .or::<String>(Err(SerializationError.into()))?,
+    ))
+}
+
+async fn fetch_thread(client: &Client, thread: ThreadID) -> Result<MessageThread, MessageError> {
+    let thread_text = get_thread_page(client, thread)
+        .await
+        .or(Err(MessageError::RequestError))?
+        .text()
+        .await
+        .or(Err(MessageError::DecodeError))?;
+
+    Ok(thread_data(thread, Html::parse_document(&thread_text)))
+}
+
+#[tauri::command]
+pub async fn get_message_thread(accounts: State<'_, Accounts>, threadid: String) -> Result<String, String> {
+    use bbs_shared::errors::MessageError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let thread = ThreadID(u64::from_str_radix(&threadid, 10).or::<String>(Err(DecodeError.into()))?);
+
+    fetch_thread(&aug_client.client, thread)
+        .await
+        .map_err(Into::<String>::into)?
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
+}
+
+#[tauri::command]
+pub async fn send_message(accounts: State<'_, Accounts>, message: String) -> Result<(), String> {
+    use bbs_shared::errors::MessageError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let message = NewMessage::from_base64(message.as_bytes()).or::<String>(Err(DecodeError.into()))?;
+
+    send_new_message(&aug_client.client, SCHOOLOGY_BASE, &message)
+        .await
+        .map_err(Into::<String>::into)
+}
+
+/// Replies to a thread and returns it re-scraped, so the new message shows up straight away.
+#[tauri::command]
+pub async fn reply_message(accounts: State<'_, Accounts>, threadid: String, body: String) -> Result<String, String> {
+    use bbs_shared::errors::MessageError::*;
+
+    let account = accounts.active();
+    let aug_client = &account.client;
+
+    let thread = ThreadID(u64::from_str_radix(&threadid, 10).or::<String>(Err(DecodeError.into()))?);
+
+    send_reply(&aug_client.client, SCHOOLOGY_BASE, thread, &body)
+        .await
+        .map_err(Into::<String>::into)?;
+
+    fetch_thread(&aug_client.client, thread)
+        .await
+        .map_err(Into::<String>::into)?
+        .to_base64()
+        .or::<String>(Err(SerializationError.into()))
+}
 
 // TODO -- ANY ASSIGNMENTS THAT HAVE <br> </br> -- REMOVE FIRST <br> AND REPLACE END TAG WITH NEW LINE
 //         can probably also figure out a way to condense the selectors --> very messy right now, but like everything else, code hard will implement later
@@ -180,13 +860,17 @@ pub async fn get_class_listing(
 // in the future, probably want to use filter view set to assignment to get all the assignments and stuff 
 #[tauri::command]
 pub async fn parse_single_class_info(
-    client: State<'_, AugClient>,
+    accounts: State<'_, Accounts>,
     classid: String
 ) -> Result<String, String> {
+    let account = accounts.active();
+    let client = &account.client;
+
     let tempclient = &client.client;
+    let class = ClassID(u64::from_str_radix(&classid, 10).map_err(|e| e.to_string())?);
     match get_single_class(tempclient, classid.clone()).await {
         Ok(res) => {
-            let (assignments, mut files, mut discussions) = {
+            let (assignments, files, discussions) = {
                 let body = res.text().await.unwrap();
                 let document = Html::parse_document(&body);
     
@@ -206,38 +890,17 @@ pub async fn parse_single_class_info(
             };
             
             // lol files are assignments because i dont know how to name things -- i'll fix this later maybe hopefully 
-            for element in files.iter_mut() {
-                let mut assignment = element;
-                let id = assignment.id.clone();
-                let doc = match get_material_info(tempclient, id).await {
-                    Ok(res) => {
-                        Ok(res.text().await.unwrap())
-                    },
-                    Err(e) => Err(e.to_string()),
-                }.unwrap();
-
-                let assignment_page = Html::parse_document(&doc);
-                let assignment_body_selector = Selector::parse(".info-body").unwrap();
-                let body = assignment_page.select(&assignment_body_selector).next().map(|element| element.text().collect::<String>()).unwrap_or_default();
-                assignment.body = body;
-            }
+            let files = stream::iter(files)
+                .map(|file| material_details(tempclient, file))
+                .buffered(MATERIAL_FETCH_LIMIT)
+                .collect::<Vec<_>>()
+                .await;
 
-            for element in discussions.iter_mut() {
-                let mut discussion = element;
-                let id = discussion.id.clone();
-                let disc = match get_class_discussions(tempclient, ClassID(u64::from_str_radix(&classid, 10).unwrap()), id).await {
-                    Ok(res) => {
-                        Ok(res.text().await.unwrap())
-                    },
-                    Err(e) => Err(e.to_string()),
-                }.unwrap();
-                    
-                let discussion_page = Html::parse_document(&disc);
-                let discussion_body_selector = Selector::parse(".discussion-prompt").unwrap();
-                let body = discussion_page.select(&discussion_body_selector).next().map(|element| element.text().collect::<String>()).unwrap_or_default();
-                discussion.body = body;
-                
-            }
+            let discussions = stream::iter(discussions)
+                .map(|discussion| discussion_details(tempclient, class, discussion))
+                .buffered(MATERIAL_FETCH_LIMIT)
+                .collect::<Vec<_>>()
+                .await;
 
             println!("{:?}", discussions);
 
@@ -253,6 +916,66 @@ pub async fn parse_single_class_info(
     }
 }
 
+/// The text and images of the first element matching `selector`.
+fn body_details(page_text: &str, selector: &str) -> (String, Vec<String>) {
+    let page = Html::parse_document(page_text);
+    let body_selector = Selector::parse(selector).unwrap();
+    let body = page.select(&body_selector).next();
+
+    (
+        body.map(|element| element.text().collect::<String>()).unwrap_or_default(),
+        body.map(body_images).unwrap_or_default(),
+    )
+}
+
+/// Fills in what only a material's own pages have: its body and where its submission stands.
+async fn material_details(client: &Client, mut material: Assignment) -> Assignment {
+    let id = material.id;
+
+    match get_material_info(client, id).await {
+        Ok(res) => match res.text().await {
+            Ok(text) => {
+                let (body, images) = body_details(&text, ".info-body");
+                material.body = body;
+                material.images = images;
+            },
+            Err(e) => eprintln!("Failed to read material {}: {}", id.0, e),
+        },
+        Err(e) => eprintln!("Failed to get material {}: {}", id.0, e),
+    }
+
+    // files and links have no dropbox, so only assignments have a submission to look up
+    if material.kind == AssignmentType::Assignment {
+        material.submission = match get_assignment_page(client, SCHOOLOGY_BASE, id).await.and_then(Response::error_for_status) {
+            Ok(res) => res.text().await.ok().map(|text| submission_data(&Html::parse_document(&text))),
+            Err(e) => {
+                eprintln!("Failed to get submission for {}: {}", id.0, e);
+                None
+            },
+        };
+    }
+
+    material
+}
+
+async fn discussion_details(client: &Client, class: ClassID, mut discussion: Assignment) -> Assignment {
+    let id = discussion.id;
+
+    match get_class_discussions(client, class, id).await {
+        Ok(res) => match res.text().await {
+            Ok(text) => {
+                let (body, images) = body_details(&text, ".discussion-prompt");
+                discussion.body = body;
+                discussion.images = images;
+            },
+            Err(e) => eprintln!("Failed to read discussion {}: {}", id.0, e),
+        },
+        Err(e) => eprintln!("Failed to get discussion {}: {}", id.0, e),
+    }
+
+    discussion
+}
+
 pub fn assignment_data (document : Html) -> Vec<Assignment> {
     // selecting all assignments
     let assignment_selector = Selector::parse("tr.type-assignment").unwrap();
@@ -282,7 +1005,9 @@ pub fn assignment_data (document : Html) -> Vec<Assignment> {
                 kind : AssignmentType::Assignment,
                 title: title.inner_html(),
                 body: "".to_string(),
+                images: Vec::new(),
                 duedate,
+                submission: None,
             });
     }
 
@@ -323,7 +1048,9 @@ pub fn file_data (document : Html) -> Vec<Assignment> {
                     kind : AssignmentType::File,
                     title: actual_title,
                     body: "".to_string(),
+                    images: Vec::new(),
                     duedate : "No Due Date Specified".to_string(),
+                    submission: None,
                 }
             } else {
                 // link case
@@ -333,7 +1060,9 @@ pub fn file_data (document : Html) -> Vec<Assignment> {
                     kind : AssignmentType::Link,
                     title: el.inner_html(),
                     body: "".to_string(),
+                    images: Vec::new(),
                     duedate : "No Due Date Specified".to_string(),
+                    submission: None,
                 }
             }            
         })
@@ -363,9 +1092,542 @@ pub fn discussion_data (document: Html) -> Vec<Assignment> {
                 kind : AssignmentType::Discussion,
                 title: title.inner_html(),
                 body: "".to_string(),
+                images: Vec::new(),
                 duedate : "No Due Date Specified".to_string(),
+                submission: None,
             });
     }
 
     discussions
-}
\ No newline at end of file
+}
+
+fn section_from_href(href: &str) -> Option<SectionID> {
+    let id = href
+        .strip_prefix("/course/")?
+        .split('/')
+        .next()?;
+
+    u64::from_str_radix(id, 10).ok().map(SectionID)
+}
+
+fn material_from_href(href: &str) -> Option<(MaterialID, AssignmentType)> {
+    let kind = if href.starts_with("/assignment/") {
+        AssignmentType::Assignment
+    } else if href.contains("/materials/discussion/view/") {
+        AssignmentType::Discussion
+    } else if href.contains("/materials/link/view/") {
+        AssignmentType::Link
+    } else if href.contains("/materials/gp/") {
+        AssignmentType::File
+    } else {
+        return None;
+    };
+
+    let id = href
+        .split('/')
+        .filter_map(|segment| u64::from_str_radix(segment, 10).ok())
+        .last()?;
+
+    Some((MaterialID(id), kind))
+}
+
+pub fn feed_data(document: Html) -> Vec<FeedItem> {
+    let item_selector = Selector::parse("li[id^='edge-assoc-']").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let author_selector = Selector::parse(".update-sentence-inner a").unwrap();
+    let body_selector = Selector::parse(".update-body").unwrap();
+    let posted_selector = Selector::parse(".small.gray").unwrap();
+
+    document
+        .select(&item_selector)
+        .map(|element| {
+            let links: Vec<_> = element
+                .select(&link_selector)
+                .filter_map(|link| Some((link.value().attr("href")?, link.text().collect::<String>())))
+                .collect();
+
+            let section = links
+                .iter()
+                .filter_map(|(href, _)| section_from_href(href))
+                .next();
+            let material = links
+                .iter()
+                .filter_map(|(href, title)| Some((material_from_href(href)?, title.trim().to_owned())))
+                .next();
+
+            let posted = element
+                .select(&posted_selector)
+                .next()
+                .map(|posted| posted.text().collect::<String>().trim().to_owned())
+                .unwrap_or_default();
+
+            match (section, material) {
+                (Some(section), material) if edge_type(element).map_or(false, |edge_type| edge_type.starts_with("grade")) => FeedItem::Graded {
+                    section,
+                    material: material.as_ref().map(|((id, _), _)| *id),
+                    title: material.map(|(_, title)| title).unwrap_or_default(),
+                    posted,
+                },
+                (Some(section), Some(((material, kind), title))) => FeedItem::NewMaterial {
+                    section,
+                    material,
+                    kind,
+                    title,
+                    posted,
+                },
+                (section, _) => FeedItem::Update {
+                    author: element
+                        .select(&author_selector)
+                        .next()
+                        .map(|author| author.text().collect::<String>().trim().to_owned())
+                        .unwrap_or_default(),
+                    section,
+                    body: element
+                        .select(&body_selector)
+                        .next()
+                        .map(|body| body.text().collect::<String>().trim().to_owned())
+                        .unwrap_or_default(),
+                    posted,
+                },
+            }
+        })
+        .collect()
+}
+
+// rows in the gradebook table are flat, so periods/categories/items are nested by the order they show up in
+pub fn grades_data(document: Html) -> Vec<GradeReport> {
+    let course_selector = Selector::parse(".gradebook-course").unwrap();
+    let course_link_selector = Selector::parse(".gradebook-course-title a[href]").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let title_selector = Selector::parse(".title").unwrap();
+    let link_selector = Selector::parse(".title a[href]").unwrap();
+    let weight_selector = Selector::parse(".percentage-contrib").unwrap();
+    let grade_selector = Selector::parse(".awarded-grade").unwrap();
+    let max_selector = Selector::parse(".max-grade").unwrap();
+    let comment_selector = Selector::parse(".comment").unwrap();
+
+    let text_of = |element: scraper::ElementRef, selector: &Selector| element
+        .select(selector)
+        .next()
+        .map(|found| found.text().collect::<String>().trim().to_owned())
+        .filter(|text| !text.is_empty());
+
+    let number_in = |text: String| text
+        .trim_matches(|c: char| !c.is_ascii_digit() && c != '.' && c != '-')
+        .parse::<f64>()
+        .ok();
+
+    document
+        .select(&course_selector)
+        .filter_map(|course| {
+            let section = course
+                .select(&course_link_selector)
+                .filter_map(|link| section_from_href(link.value().attr("href")?))
+                .next()?;
+
+            let mut periods: Vec<GradingPeriod> = Vec::new();
+
+            for row in course.select(&row_selector) {
+                let row_classes = row.value().attr("class").unwrap_or_default();
+
+                let title = text_of(row, &title_selector).unwrap_or_default();
+                let weight = text_of(row, &weight_selector).and_then(number_in);
+                let title = match title.find('(') {
+                    Some(index) if weight.is_some() => title[..index].trim().to_owned(),
+                    _ => title,
+                };
+
+                if row_classes.contains("period-row") {
+                    periods.push(GradingPeriod {
+                        name: title,
+                        grade: text_of(row, &grade_selector).map(|grade| Grade::from(grade.as_str())),
+                        categories: Vec::new(),
+                    });
+                } else if row_classes.contains("category-row") {
+                    if let Some(period) = periods.last_mut() {
+                        period.categories.push(GradeCategory {
+                            name: title,
+                            weight,
+                            grade: text_of(row, &grade_selector).map(|grade| Grade::from(grade.as_str())),
+                            items: Vec::new(),
+                        });
+                    }
+                } else if row_classes.contains("item-row") {
+                    if let Some(category) = periods.last_mut().and_then(|period| period.categories.last_mut()) {
+                        category.items.push(GradedItem {
+                            material: row
+                                .select(&link_selector)
+                                .filter_map(|link| Some(material_from_href(link.value().attr("href")?)?.0))
+                                .next(),
+                            title,
+                            score: text_of(row, &grade_selector).and_then(number_in),
+                            max: text_of(row, &max_selector).and_then(number_in),
+                            comment: text_of(row, &comment_selector),
+                        });
+                    }
+                }
+            }
+
+            Some(GradeReport {
+                section,
+                periods,
+            })
+        })
+        .collect()
+}
+
+/// Images embedded in a material's body, resolved against Schoology and proxied through the asset scheme.
+fn body_images(body: ElementRef) -> Vec<String> {
+    let image_selector = Selector::parse("img[src]").unwrap();
+
+    body
+        .select(&image_selector)
+        .filter_map(|image| image.value().attr("src"))
+        .filter_map(|src| Url::parse(SCHOOLOGY_BASE).ok()?.join(src).ok())
+        .map(|url| remote_url(url.as_str()))
+        .collect()
+}
+
+pub fn submission_data(document: &Html) -> Submission {
+    let dropbox_selector = Selector::parse(".dropbox-submission").unwrap();
+    let file_selector = Selector::parse(".attachments-file-name a[href]").unwrap();
+    let submitted_at_selector = Selector::parse(".submitted-date, .submission-time").unwrap();
+    let late_selector = Selector::parse(".late-submission, .is-late").unwrap();
+    let grade_selector = Selector::parse(".grading-grade .awarded-grade").unwrap();
+    let excused_selector = Selector::parse(".exception-excused, .exception-icon.excused").unwrap();
+
+    let dropbox = document.select(&dropbox_selector).next();
+
+    let files: Vec<_> = dropbox
+        .iter()
+        .flat_map(|dropbox| dropbox.select(&file_selector))
+        .map(|link| {
+            let name = link.text().collect::<String>().trim().to_owned();
+            let href = link.value().attr("href").unwrap_or_default();
+            let url = if href.starts_with('/') {
+                format!("{}{}", SCHOOLOGY_BASE, href)
+            } else {
+                href.to_owned()
+            };
+            let is_image = [".png", ".jpg", ".jpeg", ".gif", ".webp"]
+                .iter()
+                .any(|extension| name.to_lowercase().ends_with(extension));
+
+            SubmittedFile {
+                preview: is_image.then(|| remote_url(&url)),
+                name,
+                url,
+            }
+        })
+        .collect();
+
+    let submitted_at = dropbox
+        .and_then(|dropbox| dropbox.select(&submitted_at_selector).next())
+        .map(|date| date.text().collect::<String>().trim().to_owned())
+        .filter(|date| !date.is_empty());
+
+    let is_graded = document
+        .select(&grade_selector)
+        .next()
+        .map(|grade| !grade.text().collect::<String>().trim().is_empty())
+        .unwrap_or(false);
+
+    let state = if document.select(&excused_selector).next().is_some() {
+        SubmissionState::Excused
+    } else if is_graded {
+        SubmissionState::Graded
+    } else if files.is_empty() && submitted_at.is_none() {
+        SubmissionState::NotSubmitted
+    } else if document.select(&late_selector).next().is_some() {
+        SubmissionState::Late
+    } else {
+        SubmissionState::Submitted
+    };
+
+    Submission {
+        state,
+        submitted_at,
+        files,
+    }
+}
+
+pub fn member_data(document: Html) -> MemberPage {
+    let row_selector = Selector::parse("tr.enrollment-user").unwrap();
+    let name_selector = Selector::parse(".user-name a[href^='/user/']").unwrap();
+    let avatar_selector = Selector::parse(".profile-picture img[src]").unwrap();
+    let next_selector = Selector::parse(".pager-next a, li.next a").unwrap();
+
+    let members = document
+        .select(&row_selector)
+        .filter_map(|row| {
+            let link = row.select(&name_selector).next()?;
+            let classes = row.value().attr("class").unwrap_or_default();
+
+            Some(Member {
+                name: link.text().collect::<String>().trim().to_owned(),
+                role: if classes.split_whitespace().any(|class| class == "admin" || class == "is-admin") {
+                    MemberRole::Admin
+                } else {
+                    MemberRole::Member
+                },
+                avatar: row
+                    .select(&avatar_selector)
+                    .next()
+                    .and_then(|img| img.value().attr("src"))
+                    .map(remote_url),
+                profile_url: link
+                    .value()
+                    .attr("href")
+                    .map(|href| format!("{}{}", SCHOOLOGY_BASE, href)),
+            })
+        })
+        .collect();
+
+    MemberPage {
+        members,
+        has_next: document.select(&next_selector).next().is_some(),
+    }
+}
+
+pub fn email_data(document: &Html) -> Option<String> {
+    let mailto_selector = Selector::parse("a[href^='mailto:']").unwrap();
+
+    document
+        .select(&mailto_selector)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+        .map(|href| href.trim_start_matches("mailto:").to_owned())
+}
+
+pub fn inbox_data(document: Html) -> Vec<ThreadSummary> {
+    let row_selector = Selector::parse("table.messages-table tbody tr").unwrap();
+    let subject_selector = Selector::parse(".message-subject a[href*='/messages/view/']").unwrap();
+    let participant_selector = Selector::parse(".message-participants a, .message-author a").unwrap();
+    let snippet_selector = Selector::parse(".message-snippet, .message-preview").unwrap();
+    let updated_selector = Selector::parse(".message-date, .date-header").unwrap();
+
+    document
+        .select(&row_selector)
+        .filter_map(|row| {
+            let subject = row.select(&subject_selector).next()?;
+            let id = subject
+                .value()
+                .attr("href")?
+                .split("/messages/view/")
+                .nth(1)?
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()?;
+
+            let text_of = |selector: &Selector| row
+                .select(selector)
+                .next()
+                .map(|element| element.text().collect::<String>().trim().to_owned())
+                .unwrap_or_default();
+
+            Some(ThreadSummary {
+                id: ThreadID(id),
+                subject: subject.text().collect::<String>().trim().to_owned(),
+                participants: row
+                    .select(&participant_selector)
+                    .map(|participant| participant.text().collect::<String>().trim().to_owned())
+                    .collect(),
+                snippet: text_of(&snippet_selector),
+                updated: text_of(&updated_selector),
+                unread: row
+                    .value()
+                    .attr("class")
+                    .map(|classes| classes.split_whitespace().any(|class| class == "unread"))
+                    .unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+pub fn thread_data(id: ThreadID, document: Html) -> MessageThread {
+    let subject_selector = Selector::parse("#center-top .page-title, h2.page-title").unwrap();
+    let participant_selector = Selector::parse(".message-recipients a").unwrap();
+    let message_selector = Selector::parse(".privatemsg-message").unwrap();
+    let author_selector = Selector::parse(".message-author a, .privatemsg-author-name").unwrap();
+    let body_selector = Selector::parse(".message-text, .privatemsg-message-body").unwrap();
+    let sent_selector = Selector::parse(".message-date, .privatemsg-message-date").unwrap();
+
+    let messages = document
+        .select(&message_selector)
+        .map(|message| {
+            let text_of = |selector: &Selector| message
+                .select(selector)
+                .next()
+                .map(|element| element.text().collect::<String>().trim().to_owned())
+                .unwrap_or_default();
+
+            Message {
+                author: text_of(&author_selector),
+                body: text_of(&body_selector),
+                sent: text_of(&sent_selector),
+            }
+        })
+        .collect();
+
+    MessageThread {
+        id,
+        subject: document
+            .select(&subject_selector)
+            .next()
+            .map(|subject| subject.text().collect::<String>().trim().to_owned())
+            .unwrap_or_default(),
+        participants: document
+            .select(&participant_selector)
+            .map(|participant| participant.text().collect::<String>().trim().to_owned())
+            .collect(),
+        messages,
+    }
+}
+
+/// Schoology tags each notification with an `s-edge-type-*` class; when that's missing, the link target
+/// still says what it points at. Only markup is checked, so a class named "Graphic Design" stays a material.
+/// The `s-edge-type-*` class Schoology puts on an update or on one of its children, without the prefix.
+fn edge_type(element: ElementRef) -> Option<&str> {
+    let edge_type_selector = Selector::parse("[class*='s-edge-type-']").unwrap();
+
+    std::iter::once(element)
+        .chain(element.select(&edge_type_selector))
+        .flat_map(|element| element.value().classes())
+        .find_map(|class| class.strip_prefix("s-edge-type-"))
+}
+
+fn notification_kind(element: ElementRef, links: &[&str], has_material: bool) -> NotificationKind {
+    match edge_type(element) {
+        Some(edge_type) if edge_type.starts_with("grade") => return NotificationKind::Grade,
+        Some(edge_type) if edge_type.starts_with("comment") => return NotificationKind::Comment,
+        _ => (),
+    }
+
+    if links.iter().any(|href| href.contains("/grades") || href.contains("/grade_report")) {
+        NotificationKind::Grade
+    } else if links.iter().any(|href| href.contains("#comment") || href.contains("/comment")) {
+        NotificationKind::Comment
+    } else if has_material {
+        NotificationKind::Material
+    } else {
+        NotificationKind::Other
+    }
+}
+
+pub fn notification_data(document: Html) -> Vec<Notification> {
+    let item_selector = Selector::parse("li[id^='notification-'], .notification-item[data-id]").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let posted_selector = Selector::parse(".notification-time, .small.gray").unwrap();
+
+    document
+        .select(&item_selector)
+        .filter_map(|element| {
+            let id = element
+                .value()
+                .attr("data-id")
+                .or_else(|| element.value().attr("id")?.strip_prefix("notification-"))
+                .and_then(|id| u64::from_str_radix(id, 10).ok())?;
+
+            let links: Vec<_> = element
+                .select(&link_selector)
+                .filter_map(|link| link.value().attr("href"))
+                .collect();
+
+            let section = links.iter().filter_map(|href| section_from_href(href)).next();
+            let material = links.iter().filter_map(|href| material_from_href(href)).next();
+
+            let kind = notification_kind(element, &links, material.is_some());
+
+            let posted = element
+                .select(&posted_selector)
+                .next()
+                .map(|posted| posted.text().collect::<String>().trim().to_owned())
+                .unwrap_or_default();
+
+            let text = element
+                .text()
+                .collect::<String>()
+                .replace(&posted, "")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Some(Notification {
+                id: NotificationID(id),
+                kind,
+                text,
+                section,
+                material: material.map(|(material, _)| material),
+                posted,
+            })
+        })
+        .collect()
+}
+
+pub fn upcoming_data(document: Html) -> Vec<UpcomingItem> {
+    let event_selector = Selector::parse(".upcoming-event[data-start]").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    document
+        .select(&event_selector)
+        .filter_map(|element| {
+            let due = element.value().attr("data-start")?.parse().ok()?;
+
+            let links: Vec<_> = element
+                .select(&link_selector)
+                .filter_map(|link| Some((link.value().attr("href")?, link.text().collect::<String>())))
+                .collect();
+
+            let section = links.iter().filter_map(|(href, _)| section_from_href(href)).next()?;
+            let material = links
+                .iter()
+                .filter_map(|(href, title)| Some((material_from_href(href)?.0, title.trim().to_owned())))
+                .next();
+
+            Some(UpcomingItem {
+                section,
+                material: material.as_ref().map(|(id, _)| *id),
+                title: material.map(|(_, title)| title).unwrap_or_default(),
+                due,
+            })
+        })
+        .collect()
+}
+
+pub fn children_data(document: Html) -> ParentInfo {
+    let child_selector = Selector::parse("a[href*='/parent/switch_child/']").unwrap();
+
+    let mut parent_info = ParentInfo::default();
+
+    for link in document.select(&child_selector) {
+        let id = link
+            .value()
+            .attr("href")
+            .and_then(|href| href.split("/parent/switch_child/").nth(1))
+            .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|id| u64::from_str_radix(id, 10).ok())
+            .map(ChildID);
+
+        let id = match id {
+            Some(id) if !parent_info.children.iter().any(|child| child.id == id) => id,
+            _ => continue,
+        };
+
+        let is_active = link
+            .value()
+            .classes()
+            .chain(link.parent().and_then(|parent| parent.value().as_element()).into_iter().flat_map(|parent| parent.classes()))
+            .any(|class| class == "active" || class == "selected");
+        if is_active {
+            parent_info.active = Some(id);
+        }
+
+        parent_info.children.push(Child {
+            id,
+            name: link.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "),
+        });
+    }
+
+    parent_info
+}
@@ -0,0 +1,118 @@
+use std::{fs, path::PathBuf, time::{Duration, SystemTime}};
+
+use bbs_shared::accounts::AccountID;
+
+/// Entries older than this are fetched again, so a changed picture doesn't stick around forever.
+const MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+/// Per account; the oldest entries go first once it's over.
+const MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Blob cache on disk, keyed by an arbitrary string (usually the source url). The managed one is only a root;
+/// entries go through `for_account`.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: Option<PathBuf>,
+}
+
+// FNV-1a, so file names stay stable across builds (unlike `DefaultHasher`)
+fn hash_key(key: &str) -> u64 {
+    key
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+impl DiskCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        if let Some(dir) = &dir {
+            if let Err(e) = fs::create_dir_all(dir) {
+                eprintln!("Failed to create cache directory {:?}: {}", dir, e);
+            }
+        }
+        Self { dir }
+    }
+
+    /// A cache inside this one for a single account, so nothing fetched with one account's session is ever
+    /// served to another.
+    pub fn for_account(&self, account: &AccountID) -> Self {
+        Self::new(self.dir.as_ref().map(|dir| dir.join(format!("{:016x}", hash_key(&account.0)))))
+    }
+
+    fn path_for(&self, key: &str) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{:016x}", hash_key(key))))
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path_for(key)?;
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?
+            .elapsed()
+            .unwrap_or_default();
+
+        if age > MAX_AGE {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Failed to remove expired cache entry {:?}: {}", path, e);
+            }
+            return None;
+        }
+
+        fs::read(path).ok()
+    }
+
+    /// Deletes every entry, leaving the directory itself in place.
+    pub fn clear(&self) {
+        let entries = match self.dir.as_ref().map(fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            Some(Err(e)) => return eprintln!("Failed to read cache directory: {}", e),
+            None => return,
+        };
+
+        for entry in entries.flatten() {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                eprintln!("Failed to remove cache entry {:?}: {}", entry.path(), e);
+            }
+        }
+    }
+
+    pub fn put(&self, key: &str, data: &[u8]) {
+        if let Some(path) = self.path_for(key) {
+            if let Err(e) = fs::write(&path, data) {
+                eprintln!("Failed to write cache entry {:?}: {}", path, e);
+            }
+            self.trim();
+        }
+    }
+
+    /// Deletes the least recently written entries until the cache fits in `MAX_SIZE`.
+    fn trim(&self) {
+        let entries = match self.dir.as_ref().map(fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            Some(Err(e)) => return eprintln!("Failed to read cache directory: {}", e),
+            None => return,
+        };
+
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok().filter(|metadata| metadata.is_file())?;
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect();
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= MAX_SIZE {
+            return;
+        }
+
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in entries {
+            if total <= MAX_SIZE {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => total -= len,
+                Err(e) => eprintln!("Failed to remove cache entry {:?}: {}", path, e),
+            }
+        }
+    }
+}
@@ -1,27 +1,70 @@
 pub mod commands;
 pub mod requests;
 pub mod structs;
+pub mod settings;
+pub mod disk_cache;
+pub mod assets;
+pub mod accounts;
+pub mod session;
+pub mod sso;
+pub mod secret;
 
 use std::sync::{Arc, Mutex};
-use serde::{Serialize, Deserialize};
 
+use bbs_shared::errors::CredSetError;
+use secret::SecretString;
 
-#[derive(Debug, Serialize, Deserialize)]
+
+/// The app's own window. Commands are only accepted from here, never from the SSO window's remote pages.
+pub const MAIN_WINDOW: &str = "main";
+
+
+/// What was typed into the login page. The password only stays until a login goes through with it.
+#[derive(Debug, Default)]
 pub struct Credentials {
     username: Mutex<Arc<String>>,
-    password: Mutex<Arc<String>>,
+    password: Mutex<SecretString>,
 }
 
-impl Default for Credentials {
-    fn default() -> Self {
-        Self {
-            username: Mutex::new(Default::default()),
-            password: Mutex::new(Default::default()),
-        }
+
+impl Credentials {
+    pub fn set(&self, username: String, password: SecretString) -> Result<(), CredSetError> {
+        *self.username.lock().or(Err(CredSetError))? = Arc::new(username);
+        *self.password.lock().or(Err(CredSetError))? = password;
+        Ok(())
     }
-}
 
+    pub fn username(&self) -> String {
+        self.username
+            .lock()
+            .map(|username| (**username).clone())
+            .unwrap_or_default()
+    }
 
-unsafe impl Sync for Credentials {}
+    pub fn password(&self) -> SecretString {
+        self.password
+            .lock()
+            .map(|password| password.clone())
+            .unwrap_or_default()
+    }
 
+    pub fn is_set(&self) -> bool {
+        !self.username().is_empty() && !self.password().is_empty()
+    }
+
+    /// Keeps the username for labelling the account, but drops the password (zeroing it).
+    pub fn clear_password(&self) {
+        match self.password.lock() {
+            Ok(mut password) => *password = SecretString::default(),
+            Err(e) => eprintln!("Failed to get lock on password: {:#?}", e),
+        }
+    }
 
+    pub fn clear(&self) {
+        match self.username.lock() {
+            Ok(mut username) => *username = Default::default(),
+            Err(e) => eprintln!("Failed to get lock on username: {:#?}", e),
+        }
+        self.clear_password();
+    }
+}
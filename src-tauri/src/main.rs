@@ -4,59 +4,33 @@
     windows_subsystem = "windows"
 )]
 
-use std::sync::Arc;
-
-use bbs_shared::cache::BackendCache;
-use keyring::Entry;
-use reqwest::Client;
-use cookie_store::{CookieStore, Cookie};
-use reqwest_cookie_store::CookieStoreMutex;
-use app::{commands::*, Credentials, structs::AugClient};
-
-#[cfg(debug_assertions)]
-const ENABLE_KEYRING: bool = false;
-
-#[cfg(not(debug_assertions))]
-const ENABLE_KEYRING: bool = true;
+use app::{commands::*, accounts::Accounts, session::open_store, sso::{handle_sso_request, SSO_SCHEME}, MAIN_WINDOW, settings::SettingsStore, disk_cache::DiskCache, assets::{handle_asset_request, ASSET_SCHEME}};
+use tauri::Manager;
 
 fn main() {
-    let keyring_entry = ENABLE_KEYRING.then(|| Entry::new("dev.skyc.betterbetterschoology.cookies", "default"));
-    let mut raw_cookie_store = CookieStore::default();
-
-    if let Some(keyring_entry) = &keyring_entry {
-        if let Ok(json) = keyring_entry.get_password() {
-            if let Ok(store) = bincode::deserialize::<Vec<Cookie>>(&base64::decode(json).unwrap()) {
-                store
-                    .iter()
-                    .filter_map(|cookie| raw_cookie_store
-                        .insert(cookie.clone(), &url::Url::parse(&format!("https://{}", cookie.domain.as_cow().unwrap())).unwrap())
-                        .err()
-                        .map(|error| (cookie, error))
-                    )
-                    .for_each(|(cookie, error)| println!("Failed to insert cookie {:?}! Failed with: {}", cookie, error));
-            }
-        }
-    }
-    let mutable_cookie_store = CookieStoreMutex::new(raw_cookie_store);
-    let final_cookie_jar = Arc::new(mutable_cookie_store);
-
-    let final_cookie_jar_state  = final_cookie_jar.clone();
-    let final_cookie_jar_client = final_cookie_jar.clone();
-    drop(final_cookie_jar);
-
-    let client = Client::builder().cookie_provider(final_cookie_jar_client).build().unwrap();
-
-    let augmented_client = AugClient {
-        client,
-        cookies: final_cookie_jar_state
-    };
+    let handler = tauri::generate_handler![is_logged_in, get_class_listing, set_credentials, set_remember_password, submit_login_code, cancel_login_code, start_sso, list_accounts, switch_account, add_account, get_children, switch_child, logout, parse_single_class_info, get_home_feed, get_upcoming, get_grades, get_settings, set_settings, get_submission, submit_assignment, get_class_instructors, get_class_members, get_notifications, get_inbox, get_message_thread, send_message, reply_message];
 
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![get_class_listing, set_credentials, parse_single_class_info])
-        .manage(augmented_client)
-        .manage(Credentials::default())
-        .manage(keyring_entry)
-        .manage(BackendCache::default())
+        // remote pages in other windows (SSO) must never reach a command
+        .invoke_handler(move |invoke| {
+            if invoke.message.window().label() == MAIN_WINDOW {
+                handler(invoke);
+            } else {
+                invoke.resolver.reject("Commands are only available to the main window");
+            }
+        })
+        .register_uri_scheme_protocol(ASSET_SCHEME, handle_asset_request)
+        .register_uri_scheme_protocol(SSO_SCHEME, handle_sso_request)
+        .setup(|app| {
+            let app_dir = app.path_resolver().app_dir();
+            let settings = SettingsStore::load(app_dir.as_ref().map(|dir| dir.join("settings.json")));
+            let session_store = open_store(settings.session_store(), app_dir.clone());
+
+            app.manage(settings);
+            app.manage(DiskCache::new(app_dir.as_ref().map(|dir| dir.join("cache"))));
+            app.manage(Accounts::load(app_dir.map(|dir| dir.join("accounts.json")), session_store));
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 
@@ -1,65 +1,136 @@
-use std::{error::Error, collections::HashMap, borrow::Cow, fmt::Display, ops::Deref, sync::Arc};
+use std::{error::Error, collections::HashMap, borrow::Cow, fmt::Display, sync::{Arc, atomic::{AtomicU64, Ordering}}};
 
-use bbs_shared::{errors::LoginError, MaterialID, ClassID};
-use keyring::Entry;
-use reqwest::{Client, Response, Method};
-use reqwest_cookie_store::CookieStoreMutex;
-use scraper::{Html, Selector};
+use bbs_shared::{accounts::ChildID, errors::{LoginError, MessageError, UploadError}, messages::{NewMessage, ThreadID}, events::UploadProgress, MaterialID, ClassID, SectionID};
+use futures_util::StreamExt;
+use reqwest::{Body, Client, Response, Method, StatusCode, Url, multipart::{Form, Part}};
+use scraper::{ElementRef, Html, Selector};
 use serde::Serialize;
-use tauri::State;
+use zeroize::Zeroize;
 use derive_getters::Getters;
 
-use crate::Credentials;
+use crate::{secret::SecretString, sso::is_schoology_url};
 
 lazy_static::lazy_static! {
     static ref LOGIN_FORM: Selector = Selector::parse("form#s-user-login-form").unwrap();
-    static ref LOGIN_INPUT: Selector = Selector::parse("input").unwrap();
+    static ref LOGIN_MESSAGE: Selector = Selector::parse("div.messages .error, div.messages.error, [aria-invalid='true'] + .description").unwrap();
+    static ref ANY_FORM: Selector = Selector::parse("form").unwrap();
+    static ref CODE_INPUT: Selector = Selector::parse(
+        "input[autocomplete='one-time-code'], \
+         input[name='code']:not([type='hidden']), \
+         input[name='otp']:not([type='hidden']), \
+         input[name*='verification_code']:not([type='hidden']), \
+         input[name*='security_code']:not([type='hidden'])"
+    ).unwrap();
+    static ref LOGOUT_LINK: Selector = Selector::parse("a[href='/logout'], a[href^='/logout?'], a[href$='schoology.com/logout']").unwrap();
+    static ref CHALLENGE_HINT: Selector = Selector::parse(".description, p").unwrap();
+    static ref CAPTCHA: Selector = Selector::parse(".g-recaptcha, .h-captcha, iframe[src*='captcha'], input[name*='captcha']").unwrap();
+    static ref DROPBOX_FORM: Selector = Selector::parse("form#s-assignment-dropbox-submit-form, form[action*='dropbox']").unwrap();
+    static ref NEW_MESSAGE_FORM: Selector = Selector::parse("form#s-privatemsg-new-form").unwrap();
+    static ref REPLY_MESSAGE_FORM: Selector = Selector::parse("form#s-privatemsg-reply-form").unwrap();
+    static ref FORM_INPUT: Selector = Selector::parse("input").unwrap();
 }
 
+/// Upload functions take the base url explicitly so they can be pointed at a local mock server.
+pub const SCHOOLOGY_BASE: &str = "https://bca.schoology.com";
+
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
 
 
 #[derive(Getters, Debug, Clone)]
-pub struct LoginFormDetails {
+pub struct FormDetails {
     method: String,
     action: String,
     inputs: Vec<HashMap<String, String>>,
 }
 
-#[derive(Debug)]
-pub struct NotFoundError {
-    value: Cow<'static, str>,
+/// Schoology's login form, with the page it was on so its action can be resolved the way a browser would.
+#[derive(Debug, Clone)]
+pub struct LoginForm {
+    pub form: FormDetails,
+    pub page: Url,
+    pub status: StatusCode,
 }
-impl Display for NotFoundError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        f.write_str(&self.value)
+
+/// A verification code form Schoology showed after accepting the password.
+#[derive(Debug, Clone)]
+pub struct LoginChallenge {
+    pub form: FormDetails,
+    /// The page the form was on, which its action is relative to.
+    pub page: Url,
+    /// Schoology's instructions, e.g. where the code was sent.
+    pub hint: String,
+    /// Names of the inputs `CODE_INPUT` matched, which get the code instead of their own value.
+    pub code_inputs: Vec<String>,
+}
+
+/// Anything with a way to log out belongs to a session that's already in, whatever forms the page holds.
+fn is_signed_in_page(document: &Html) -> bool {
+    document.select(&LOGOUT_LINK).next().is_some()
+}
+
+fn scrape_challenge(document: &Html, page: &Url) -> Option<LoginChallenge> {
+    if is_signed_in_page(document) {
+        return None;
     }
+
+    let form_node = document
+        .select(&ANY_FORM)
+        .find(|form| form.select(&CODE_INPUT).next().is_some())?;
+
+    let hint = form_node
+        .select(&CHALLENGE_HINT)
+        .map(collapsed_text)
+        .find(|text| !text.is_empty())
+        .unwrap_or_default();
+
+    let code_inputs = form_node
+        .select(&CODE_INPUT)
+        .filter_map(|input| input.value().attr("name"))
+        .map(ToOwned::to_owned)
+        .collect();
+
+    Some(LoginChallenge {
+        form: form_details(form_node),
+        page: page.clone(),
+        hint,
+        code_inputs,
+    })
 }
 
-impl Error for NotFoundError {}
+/// Resolves a form action the way a browser would. An empty action posts back to the page itself.
+fn absolute_url(page: &Url, action: &str) -> Option<Url> {
+    page.join(action).ok()
+}
 
-pub async fn get_login_page(client: &Client) -> Result<LoginFormDetails, LoginError> {
-    use bbs_shared::errors::LoginError::*;
+/// What Schoology's front page turned out to be.
+#[derive(Debug, Clone)]
+pub enum LoginPage {
+    SignedIn,
+    Form(LoginForm),
+}
 
-    let output = client
-        .get("https://bca.schoology.com")
-        .send()
-        .await;
-    let res = output.or(Err(RequestError))?;
-    let text = res.text().await.or(Err(DecodeError))?;
-    let document = Html::parse_document(&text);
-    
-    let forms = document.select(&LOGIN_FORM);
+impl FormDetails {
+    /// Name/value pairs of every hidden input, i.e. the form tokens Schoology expects echoed back.
+    pub fn hidden_fields(&self) -> Vec<(String, String)> {
+        self.inputs
+            .iter()
+            .filter(|input| input.get("type").map(String::as_str) == Some("hidden"))
+            .filter_map(|input| Some((input.get("name")?.to_owned(), input.get("value").cloned().unwrap_or_default())))
+            .collect()
+    }
+}
 
-    let form_node = match forms.last() {
-        Some(node) => node,
-        None => return Err(FindFormError),
-    };
+pub fn scrape_form(document: &Html, selector: &Selector) -> Option<FormDetails> {
+    document.select(selector).last().map(form_details)
+}
+
+fn form_details(form_node: ElementRef) -> FormDetails {
 
-    let method = form_node.value().attr("method").unwrap().to_owned();
-    let action = form_node.value().attr("action").unwrap().to_owned();
+    let method = form_node.value().attr("method").unwrap_or("post").to_lowercase();
+    let action = form_node.value().attr("action").unwrap_or_default().to_owned();
 
     let inputs = form_node
-        .select(&LOGIN_INPUT)
+        .select(&FORM_INPUT)
         .map(|element_ref| element_ref.value())
         .map(|element| element
             .attrs()
@@ -67,24 +138,130 @@ pub async fn get_login_page(client: &Client) -> Result<LoginFormDetails, LoginEr
             .collect()
         )
         .collect();
-        
-    Ok(LoginFormDetails {
+
+    FormDetails {
         inputs,
         action,
         method,
-    })
+    }
 }
 
-pub async fn login(
+fn collapsed_text(element: ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug)]
+pub struct NotFoundError {
+    value: Cow<'static, str>,
+}
+impl Display for NotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(&self.value)
+    }
+}
+
+impl Error for NotFoundError {}
+
+pub async fn get_login_page(client: &Client) -> Result<LoginPage, LoginError> {
+    use bbs_shared::errors::LoginError::*;
+
+    let res = client
+        .get(SCHOOLOGY_BASE)
+        .send()
+        .await
+        .map_err(|e| NetworkUnreachable(e.to_string()))?;
+
+    // SSO schools bounce straight to their identity provider, which has no form we can fill in
+    if !is_schoology_url(res.url().as_str()) {
+        return Err(SsoRequired);
+    }
+
+    let status = res.status();
+    let url = res.url().clone();
+    let text = res.text().await.map_err(|e| DecodeError(e.to_string()))?;
+    let document = Html::parse_document(&text);
+
+    if let Some(form) = scrape_form(&document, &LOGIN_FORM) {
+        return Ok(LoginPage::Form(LoginForm { form, page: url, status }));
+    }
+
+    check_unavailable(status, &text)?;
+
+    if !status.is_success() || url.path().starts_with("/login") {
+        return Err(UnexpectedPage { url: url.to_string(), status: status.as_u16() });
+    }
+
+    Ok(LoginPage::SignedIn)
+}
+
+/// Schoology's maintenance page comes with an error status. Ordinary pages can mention maintenance in a
+/// post or an announcement, so the text is only a hint for error responses that aren't 5xx.
+fn check_unavailable(status: StatusCode, text: &str) -> Result<(), LoginError> {
+    if status.is_success() {
+        Ok(())
+    } else if status.is_server_error() || text.to_lowercase().contains("scheduled maintenance") {
+        Err(LoginError::Maintenance { status: status.as_u16() })
+    } else {
+        Ok(())
+    }
+}
+
+/// Works out why Schoology sent the login form back instead of letting us in.
+fn rejected_login(document: &Html, text: &str, username: String, url: String, status: u16) -> LoginError {
+    if document.select(&CAPTCHA).next().is_some() {
+        return LoginError::CaptchaRequired { url };
+    }
+
+    let message = document
+        .select(&LOGIN_MESSAGE)
+        .next()
+        .map(collapsed_text)
+        .unwrap_or_default();
+    let lowercase = message.to_lowercase();
+
+    if lowercase.contains("captcha") {
+        LoginError::CaptchaRequired { url }
+    } else if ["locked", "blocked", "disabled", "too many"].iter().any(|word| lowercase.contains(word)) {
+        LoginError::AccountLocked(message)
+    } else if ["unrecognized", "incorrect", "invalid"].iter().any(|word| lowercase.contains(word)) || text.contains("unrecognized") {
+        LoginError::InvalidCredentials { username }
+    } else {
+        LoginError::UnexpectedPage { url, status }
+    }
+}
+
+/// Hands the identity provider's answer to Schoology, which replies by setting the session cookies.
+pub async fn submit_saml_response(
     client: &Client,
-    creds: State<'_, Credentials>,
-    cookie_jar: &Arc<CookieStoreMutex>,
-    keyring_entry: State<'_, Option<Entry>>,
-    login_form_details: LoginFormDetails,
+    action: &str,
+    fields: &HashMap<String, String>,
 ) -> Result<(), LoginError> {
+    let res = client
+        .post(action)
+        .form(fields)
+        .send()
+        .await
+        .map_err(|e| LoginError::NetworkUnreachable(e.to_string()))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(LoginError::SsoFailed(format!("Schoology answered the identity provider with status {}", res.status().as_u16())))
+    }
+}
+
+pub async fn login(
+    client: &Client,
+    username: &str,
+    password: &SecretString,
+    login_form: LoginForm,
+) -> Result<Option<LoginChallenge>, LoginError> {
     use LoginError::*;
 
-    let form: Vec<(String, String)> = login_form_details
+    let unexpected_page = || UnexpectedPage { url: login_form.page.to_string(), status: login_form.status.as_u16() };
+    let url = absolute_url(&login_form.page, login_form.form.action()).ok_or_else(unexpected_page)?;
+
+    let mut form: Vec<(String, String)> = login_form.form
         .inputs()
         .iter()
         .filter_map(|element| Some((element.get("name")?, element)))
@@ -93,23 +270,9 @@ pub async fn login(
             |(name, input)| match input.get("value") {
                 Some(value) if value != "" => Some((name.to_owned(), value.to_owned())),
                 _ => if name.contains("pass") {
-                    Some((
-                        name.to_owned(),
-                        creds.password
-                            .lock()
-                            .ok()
-                            .map(|mutex_guard| (*mutex_guard).deref().clone())
-                            .unwrap_or_default()
-                    ))
+                    Some((name.to_owned(), password.expose().to_owned()))
                 } else if name.contains("mail") {
-                    Some((
-                        name.to_owned(),
-                        creds.username
-                            .lock()
-                            .ok()
-                            .map(|mutex_guard| (*mutex_guard).deref().clone())
-                            .unwrap_or_default()
-                    ))
+                    Some((name.to_owned(), username.to_owned()))
                 } else {
                     None
                 }
@@ -117,50 +280,94 @@ pub async fn login(
         )
         .collect();
         
-    let response = match login_form_details.method().as_str() {
-        "post" => client.post(format!("https://bca.schoology.com{}", login_form_details.action())).form(&form),
-        "get"  => client.get (format!("https://bca.schoology.com{}", login_form_details.action())),
-        _ => unimplemented!("Invalid method form method: `{}`!", login_form_details.method()),
+    let request = match login_form.form.method().as_str() {
+        "post" => Some(client.post(url).form(&form)),
+        "get" => Some(client.get(url).query(&form)),
+        _ => None,
+    };
+    let response = match request {
+        Some(request) => Some(request.send().await),
+        None => None,
+    };
+
+    // the password was copied in as a plain value
+    form.iter_mut().for_each(|(_, value)| value.zeroize());
+
+    let res = response.ok_or_else(unexpected_page)?.map_err(|e| NetworkUnreachable(e.to_string()))?;
+    let status = res.status();
+    let page = res.url().clone();
+    let url = page.to_string();
+    let text = res.text().await.map_err(|e| DecodeError(e.to_string()))?;
+
+    check_unavailable(status, &text)?;
+
+    let document = Html::parse_document(&text);
+    if document.select(&LOGIN_FORM).next().is_some() {
+        return Err(rejected_login(&document, &text, username.to_owned(), url, status.as_u16()));
     }
-        .send()
-        .await;
-    
-    
 
-    match response {
-        Ok(res) => {
-            let status = res.status();
+    if let Some(challenge) = scrape_challenge(&document, &page) {
+        return Ok(Some(challenge));
+    }
 
+    if status.is_success() {
+        // the cookie provider saves the new session as the response comes in
+        Ok(None)
+    } else {
+        Err(UnexpectedPage { url, status: status.as_u16() })
+    }
+}
 
-            if status.is_success() {
-                let text = res.text().await.or(Err(DecodeError))?;
-                let index = text.find("aria-invalid");
-                if index.is_some() && text[index.unwrap()..index.unwrap() + 100].contains("unrecognized") {
-                    Err(InvalidCredsError)
-                } else {
-                    match cookie_jar.lock() {
-                        Ok(inner_jar) => {
-                            match bincode::serialize(&inner_jar.iter_unexpired().collect::<Vec<_>>()) {
-                                Ok(serialized_value)  => {
-                                    let base_64_value = base64::encode(serialized_value);
-                                    if let Some(keyring_entry) = &*keyring_entry {
-                                        if let Err(e) = keyring_entry.set_password(&base_64_value) {
-                                            eprintln!("Keyring failed to save cookies: {}", e);
-                                        }
-                                    }
-                                }
-                                Err(e) => eprintln!("Failed to serialize cookies into binary: {}", e),
-                            }
-                        },
-                        Err(e) => eprintln!("Failed to get lock on cookie jar: {}", e),
-                    }
-                    Ok(())
-                }
+/// Answers a `LoginChallenge`. Comes back with another challenge when the code was wrong.
+pub async fn send_login_code(
+    client: &Client,
+    challenge: &LoginChallenge,
+    code: &str,
+) -> Result<Option<LoginChallenge>, LoginError> {
+    use LoginError::*;
+
+    let form: Vec<(String, String)> = challenge.form
+        .inputs()
+        .iter()
+        .filter(|input| input.get("type").map(String::as_str) != Some("checkbox") || input.contains_key("checked"))
+        .filter_map(|input| {
+            let name = input.get("name")?.to_owned();
+            if challenge.code_inputs.contains(&name) {
+                Some((name, code.to_owned()))
             } else {
-                Err(LaterRequestError)
+                Some((name, input.get("value")?.to_owned()))
             }
-        },
-        Err(_) => Err(LaterRequestError),
+        })
+        .collect();
+
+    let url = absolute_url(&challenge.page, challenge.form.action())
+        .ok_or_else(|| DecodeError(format!("unusable code form action {:?}", challenge.form.action())))?;
+    let request = match challenge.form.method().as_str() {
+        "get" => client.get(url).query(&form),
+        _ => client.post(url).form(&form),
+    };
+
+    let res = request.send().await.map_err(|e| NetworkUnreachable(e.to_string()))?;
+    let status = res.status();
+    let page = res.url().clone();
+    let url = page.to_string();
+    let text = res.text().await.map_err(|e| DecodeError(e.to_string()))?;
+
+    check_unavailable(status, &text)?;
+
+    let document = Html::parse_document(&text);
+    // Schoology threw the half-finished login away, so the password has to go in again
+    if document.select(&LOGIN_FORM).next().is_some() {
+        return Err(CodeExpired);
+    }
+    if let Some(challenge) = scrape_challenge(&document, &page) {
+        return Ok(Some(challenge));
+    }
+
+    if status.is_success() {
+        Ok(None)
+    } else {
+        Err(UnexpectedPage { url, status: status.as_u16() })
     }
 }
 
@@ -190,4 +397,364 @@ pub async fn get_class_discussions(client: &Client, classid: ClassID, discussion
 
 pub async fn get_material_info(client: &Client, materialid: MaterialID) -> Result<Response, reqwest::Error> {
     client.get(format!("https://bca.schoology.com/assignment/{}/info", materialid.0)).send().await
-}
\ No newline at end of file
+}
+
+pub async fn get_assignment_page(client: &Client, base_url: &str, materialid: MaterialID) -> Result<Response, reqwest::Error> {
+    client.get(format!("{}/assignment/{}", base_url, materialid.0)).send().await
+}
+
+/// Schoology filters the members by `search` itself, so results aren't limited to the pages already loaded.
+pub async fn get_members_page(client: &Client, section: SectionID, search: &str, page: u32) -> Result<Response, reqwest::Error> {
+    client
+        .get(format!("https://bca.schoology.com/enrollments/edit/members/section/{}/ajax", section.0))
+        .query(&[("ss", search.to_owned()), ("p", page.to_string())])
+        .send()
+        .await
+}
+
+pub async fn get_user_info_page(client: &Client, profile_url: &str) -> Result<Response, reqwest::Error> {
+    client.get(format!("{}/info", profile_url)).send().await
+}
+
+/// An error status is an error here, so a login or error page never gets mistaken for the image.
+pub async fn get_image(client: &Client, url: &str) -> Result<Response, reqwest::Error> {
+    client.get(url).send().await?.error_for_status()
+}
+
+pub async fn get_home_feed_page(client: &Client, page: u32) -> Result<Response, reqwest::Error> {
+    client.get(format!("https://bca.schoology.com/home/feed?page={}", page)).send().await
+}
+
+pub async fn get_upcoming_page(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/home/upcoming_ajax").send().await
+}
+
+pub async fn get_grades_page(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/grades/grades").send().await
+}
+
+/// Scrapes the dropbox form along with the page it came from, which its action is relative to.
+pub async fn get_dropbox_form(client: &Client, base_url: &str, materialid: MaterialID) -> Result<(Url, FormDetails), UploadError> {
+    let response = client
+        .get(format!("{}/assignment/{}/dropbox/submit", base_url, materialid.0))
+        .send()
+        .await
+        .or(Err(UploadError::RequestError))?;
+    let page = response.url().clone();
+    let text = response.text().await.or(Err(UploadError::RequestError))?;
+
+    scrape_form(&Html::parse_document(&text), &DROPBOX_FORM)
+        .map(|form| (page, form))
+        .ok_or(UploadError::FindFormError)
+}
+
+pub async fn upload_dropbox_files(
+    client: &Client,
+    page: &Url,
+    upload: u64,
+    materialid: MaterialID,
+    form_details: &FormDetails,
+    files: Vec<(String, Vec<u8>)>,
+    on_progress: Arc<dyn Fn(UploadProgress) + Send + Sync>,
+) -> Result<(), UploadError> {
+    let file_field = form_details
+        .inputs()
+        .iter()
+        .find(|input| input.get("type").map(String::as_str) == Some("file"))
+        .and_then(|input| input.get("name").cloned())
+        .unwrap_or_else(|| "files[]".to_owned());
+
+    let file_count = files.len();
+
+    let mut form = form_details
+        .hidden_fields()
+        .into_iter()
+        .fold(Form::new(), |form, (name, value)| form.text(name, value));
+
+    for (file_index, (file_name, bytes)) in files.into_iter().enumerate() {
+        let total = bytes.len() as u64;
+        let sent = Arc::new(AtomicU64::new(0));
+        let on_progress = on_progress.clone();
+
+        let chunks: Vec<Result<Vec<u8>, std::io::Error>> = bytes
+            .chunks(UPLOAD_CHUNK_SIZE)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+
+        let stream = futures_util::stream::iter(chunks).inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                let sent = sent.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+                on_progress(UploadProgress {
+                    upload,
+                    material: materialid,
+                    file_index,
+                    file_count,
+                    sent,
+                    total,
+                });
+            }
+        });
+
+        form = form.part(
+            file_field.clone(),
+            Part::stream_with_length(Body::wrap_stream(stream), total).file_name(file_name),
+        );
+    }
+
+    // A form without an action posts back to its own page, which `join` already gives us.
+    let url = absolute_url(page, form_details.action()).ok_or(UploadError::FindFormError)?;
+
+    let response = client
+        .post(url)
+        .multipart(form)
+        .send()
+        .await
+        .or(Err(UploadError::RequestError))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(UploadError::RejectedError)
+    }
+}
+
+/// The whole submission: finds the dropbox form, uploads to it, then reads the assignment page back so the
+/// caller can confirm the files actually landed.
+pub async fn submit_dropbox(
+    client: &Client,
+    base_url: &str,
+    upload: u64,
+    materialid: MaterialID,
+    files: Vec<(String, Vec<u8>)>,
+    on_progress: Arc<dyn Fn(UploadProgress) + Send + Sync>,
+) -> Result<String, UploadError> {
+    let (page, form_details) = get_dropbox_form(client, base_url, materialid).await?;
+    upload_dropbox_files(client, &page, upload, materialid, &form_details, files, on_progress).await?;
+
+    get_assignment_page(client, base_url, materialid)
+        .await
+        .or(Err(UploadError::RequestError))?
+        .text()
+        .await
+        .or(Err(UploadError::RequestError))
+}
+
+/// Ends the session on Schoology's side, not just ours.
+pub async fn logout_remote(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/logout").send().await
+}
+
+/// Parent accounts get a child selector in the header of every page.
+pub async fn get_home_page(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/home").send().await
+}
+
+/// Schoology scopes the whole session to the selected child, `/iapi/course/active` included.
+pub async fn select_child(client: &Client, child: ChildID) -> Result<Response, reqwest::Error> {
+    client.get(format!("https://bca.schoology.com/parent/switch_child/{}", child.0)).send().await
+}
+
+pub async fn get_notifications_page(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/home/notifications").send().await
+}
+
+pub async fn get_inbox_page(client: &Client) -> Result<Response, reqwest::Error> {
+    client.get("https://bca.schoology.com/messages/inbox").send().await
+}
+
+pub async fn get_thread_page(client: &Client, thread: ThreadID) -> Result<Response, reqwest::Error> {
+    client.get(format!("https://bca.schoology.com/messages/view/{}", thread.0)).send().await
+}
+
+/// Scrapes the compose form, or the reply form at the bottom of a thread when `thread` is given, along with
+/// the page it came from.
+pub async fn get_message_form(client: &Client, base_url: &str, thread: Option<ThreadID>) -> Result<(Url, FormDetails), MessageError> {
+    let (url, selector): (_, &Selector) = match thread {
+        Some(thread) => (format!("{}/messages/view/{}", base_url, thread.0), &REPLY_MESSAGE_FORM),
+        None => (format!("{}/messages/new", base_url), &NEW_MESSAGE_FORM),
+    };
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .or(Err(MessageError::RequestError))?;
+    let page = response.url().clone();
+    let text = response.text().await.or(Err(MessageError::RequestError))?;
+
+    scrape_form(&Html::parse_document(&text), selector)
+        .map(|form| (page, form))
+        .ok_or(MessageError::FindFormError)
+}
+
+pub async fn post_message_form(
+    client: &Client,
+    page: &Url,
+    form_details: &FormDetails,
+    fields: Vec<(String, String)>,
+) -> Result<(), MessageError> {
+    let mut form = form_details.hidden_fields();
+    form.extend(fields);
+
+    let url = absolute_url(page, form_details.action()).ok_or(MessageError::DecodeError)?;
+
+    let response = client
+        .post(url)
+        .form(&form)
+        .send()
+        .await
+        .or(Err(MessageError::RequestError))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(MessageError::RejectedError)
+    }
+}
+
+pub async fn send_new_message(client: &Client, base_url: &str, message: &NewMessage) -> Result<(), MessageError> {
+    let (page, form_details) = get_message_form(client, base_url, None).await?;
+
+    let recipients = message.recipients
+        .iter()
+        .map(|recipient| recipient.0.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    post_message_form(client, &page, &form_details, vec![
+        ("recipient_list".to_owned(), recipients),
+        ("subject".to_owned(), message.subject.clone()),
+        ("message".to_owned(), message.body.clone()),
+        ("op".to_owned(), "Send".to_owned()),
+    ]).await
+}
+
+pub async fn send_reply(client: &Client, base_url: &str, thread: ThreadID, body: &str) -> Result<(), MessageError> {
+    let (page, form_details) = get_message_form(client, base_url, Some(thread)).await?;
+
+    post_message_form(client, &page, &form_details, vec![
+        ("message".to_owned(), body.to_owned()),
+        ("op".to_owned(), "Reply".to_owned()),
+    ]).await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use mockito::{mock, Matcher};
+
+    use super::*;
+
+    const LOGIN_URL: &str = "https://bca.schoology.com/login";
+
+    fn rejected(body: &str) -> LoginError {
+        let page = format!(r#"<html><body>{}<form id="s-user-login-form"></form></body></html>"#, body);
+        rejected_login(&Html::parse_document(&page), &page, "student@bca".to_owned(), LOGIN_URL.to_owned(), 200)
+    }
+
+    #[test]
+    fn explains_rejected_logins() {
+        assert_eq!(
+            rejected(r#"<div class="messages"><div class="error">Sorry, unrecognized username or password.</div></div>"#),
+            LoginError::InvalidCredentials { username: "student@bca".to_owned() },
+        );
+        assert_eq!(
+            rejected(r#"<div class="messages error">This account has been blocked after too many failed attempts.</div>"#),
+            LoginError::AccountLocked("This account has been blocked after too many failed attempts.".to_owned()),
+        );
+        assert_eq!(
+            rejected(r#"<div class="g-recaptcha"></div>"#),
+            LoginError::CaptchaRequired { url: LOGIN_URL.to_owned() },
+        );
+        assert_eq!(
+            rejected(r#"<div class="messages error">Please complete the CAPTCHA.</div>"#),
+            LoginError::CaptchaRequired { url: LOGIN_URL.to_owned() },
+        );
+        assert_eq!(
+            rejected(r#"<div class="messages error">Welcome back!</div>"#),
+            LoginError::UnexpectedPage { url: LOGIN_URL.to_owned(), status: 200 },
+        );
+    }
+
+    #[test]
+    fn only_error_responses_are_maintenance() {
+        let maintenance = "<h1>Schoology is undergoing scheduled maintenance</h1>";
+
+        assert_eq!(check_unavailable(StatusCode::OK, maintenance), Ok(()));
+        assert_eq!(check_unavailable(StatusCode::NOT_FOUND, "<h1>Page not found</h1>"), Ok(()));
+        assert_eq!(
+            check_unavailable(StatusCode::FORBIDDEN, maintenance),
+            Err(LoginError::Maintenance { status: 403 }),
+        );
+        assert_eq!(
+            check_unavailable(StatusCode::SERVICE_UNAVAILABLE, ""),
+            Err(LoginError::Maintenance { status: 503 }),
+        );
+    }
+
+    #[tokio::test]
+    async fn submits_to_the_given_base_url() {
+        let form = mock("GET", "/assignment/7/dropbox/submit")
+            .with_body(r#"<form id="s-assignment-dropbox-submit-form" action="/assignment/7/dropbox/submit" method="post">
+                <input type="hidden" name="form_token" value="token">
+                <input type="file" name="files[]">
+            </form>"#)
+            .create();
+        let post = mock("POST", "/assignment/7/dropbox/submit")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("form_token".to_owned()),
+                Matcher::Regex("essay.txt".to_owned()),
+            ]))
+            .create();
+        let confirmation = mock("GET", "/assignment/7")
+            .with_body("<div class=\"dropbox-submission\">essay.txt</div>")
+            .create();
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let on_progress = {
+            let progress = progress.clone();
+            Arc::new(move |update: UploadProgress| progress.lock().unwrap().push(update))
+        };
+
+        let page = submit_dropbox(
+            &Client::new(),
+            &mockito::server_url(),
+            3,
+            MaterialID(7),
+            vec![("essay.txt".to_owned(), b"hello".to_vec())],
+            on_progress,
+        ).await;
+
+        form.assert();
+        post.assert();
+        confirmation.assert();
+        assert!(page.unwrap().contains("essay.txt"));
+
+        let progress = progress.lock().unwrap();
+        assert!(progress.iter().all(|update| update.upload == 3 && update.material == MaterialID(7)));
+        assert_eq!(progress.last().map(|update| (update.sent, update.total)), Some((5, 5)));
+    }
+
+    #[tokio::test]
+    async fn reports_a_rejected_upload() {
+        let _form = mock("GET", "/assignment/8/dropbox/submit")
+            .with_body(r#"<form id="s-assignment-dropbox-submit-form" action="/assignment/8/dropbox/submit"></form>"#)
+            .create();
+        let _post = mock("POST", "/assignment/8/dropbox/submit")
+            .with_status(403)
+            .create();
+
+        let result = submit_dropbox(
+            &Client::new(),
+            &mockito::server_url(),
+            0,
+            MaterialID(8),
+            vec![("essay.txt".to_owned(), Vec::new())],
+            Arc::new(|_| ()),
+        ).await;
+
+        assert_eq!(result, Err(UploadError::RejectedError));
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::Debug;
+
+use serde::{Serialize, Serializer};
+use zeroize::Zeroize;
+
+/// A password or similar that never shows up in logs, and is wiped from memory once dropped.
+///
+/// `Debug` and `Serialize` both write a placeholder. The only way to get at the value is `expose`,
+/// which makes every real use easy to find.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+const REDACTED: &str = "<redacted>";
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretString({})", REDACTED)
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
@@ -0,0 +1,346 @@
+//! Where each account's cookie jar and remembered password live between launches.
+
+use std::{collections::HashMap, fmt::Debug, fs, path::{Path, PathBuf}, sync::{Arc, Mutex}};
+
+use bbs_shared::{accounts::AccountID, errors::AccountError, settings::SessionStoreKind};
+use chacha20poly1305::{aead::{Aead, NewAead}, ChaCha20Poly1305, Key, Nonce};
+use cookie_store::{CookieStore, Cookie};
+use keyring::Entry;
+use rand::{rngs::OsRng, RngCore};
+use reqwest::{cookie::CookieStore as ReqwestCookieStore, header::HeaderValue, Url};
+use reqwest_cookie_store::CookieStoreMutex;
+
+const KEYRING_SERVICE_PREFIX: &str = "dev.skyc.betterbetterschoology";
+const KEY_FILE: &str = "session.key";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Secret {
+    Cookies,
+    Credentials,
+}
+
+impl Secret {
+    fn name(&self) -> &'static str {
+        match self {
+            Secret::Cookies => "cookies",
+            Secret::Credentials => "credentials",
+        }
+    }
+}
+
+pub trait SessionStore: Debug + Send + Sync {
+    fn load(&self, account: &AccountID, secret: Secret) -> Option<String>;
+    fn save(&self, account: &AccountID, secret: Secret, value: &str) -> Result<(), AccountError>;
+    /// Deleting something that was never saved is not an error.
+    fn delete(&self, account: &AccountID, secret: Secret) -> Result<(), AccountError>;
+}
+
+/// Falls back to memory when the configured store can't be opened, so the app still runs, just without persistence.
+pub fn open_store(kind: SessionStoreKind, app_dir: Option<PathBuf>) -> Arc<dyn SessionStore> {
+    match (kind, app_dir) {
+        (SessionStoreKind::Keyring, _) => Arc::new(KeyringStore),
+        (SessionStoreKind::EncryptedFile, Some(app_dir)) => match EncryptedFileStore::open(app_dir.join("sessions")) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                eprintln!("Failed to open session directory, sessions won't be saved: {}", e);
+                Arc::new(MemoryStore::default())
+            },
+        },
+        (SessionStoreKind::EncryptedFile, None) => {
+            eprintln!("No app directory to keep sessions in, sessions won't be saved");
+            Arc::new(MemoryStore::default())
+        },
+        (SessionStoreKind::Memory, _) => Arc::new(MemoryStore::default()),
+    }
+}
+
+
+/// One keyring entry per account and secret, under the same services the app has always used.
+#[derive(Debug)]
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(account: &AccountID, secret: Secret) -> Entry {
+        Entry::new(&format!("{}.{}", KEYRING_SERVICE_PREFIX, secret.name()), &account.0)
+    }
+}
+
+impl SessionStore for KeyringStore {
+    fn load(&self, account: &AccountID, secret: Secret) -> Option<String> {
+        Self::entry(account, secret).get_password().ok()
+    }
+
+    fn save(&self, account: &AccountID, secret: Secret, value: &str) -> Result<(), AccountError> {
+        Self::entry(account, secret).set_password(value).map_err(|e| {
+            eprintln!("Keyring failed to save {}: {}", secret.name(), e);
+            AccountError::SessionStoreError
+        })
+    }
+
+    fn delete(&self, account: &AccountID, secret: Secret) -> Result<(), AccountError> {
+        match Self::entry(account, secret).delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => {
+                eprintln!("Keyring failed to delete {}: {}", secret.name(), e);
+                Err(AccountError::SessionStoreError)
+            },
+        }
+    }
+}
+
+
+/// For machines without a usable keyring. The key sits next to the files, so this keeps sessions out of
+/// backups and casual snooping, not away from someone who already has the whole app directory.
+pub struct EncryptedFileStore {
+    dir: PathBuf,
+    cipher: ChaCha20Poly1305,
+}
+
+impl Debug for EncryptedFileStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedFileStore").field("dir", &self.dir).finish_non_exhaustive()
+    }
+}
+
+impl EncryptedFileStore {
+    pub fn open(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let key_path = dir.join(KEY_FILE);
+        let key = match fs::read(&key_path) {
+            Ok(key) if key.len() == 32 => key,
+            _ => {
+                let mut key = vec![0; 32];
+                OsRng.fill_bytes(&mut key);
+                fs::write(&key_path, &key)?;
+                restrict_permissions(&key_path);
+                key
+            },
+        };
+
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            dir,
+        })
+    }
+
+    fn path_for(&self, account: &AccountID, secret: Secret) -> PathBuf {
+        self.dir.join(format!("{}.{}", account.0, secret.name()))
+    }
+}
+
+impl SessionStore for EncryptedFileStore {
+    fn load(&self, account: &AccountID, secret: Secret) -> Option<String> {
+        let sealed = fs::read(self.path_for(account, secret)).ok()?;
+        if sealed.len() < NONCE_LEN {
+            return None;
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        match self.cipher.decrypt(Nonce::from_slice(nonce), ciphertext) {
+            Ok(plaintext) => String::from_utf8(plaintext).ok(),
+            Err(_) => {
+                eprintln!("Saved {} for {} failed to decrypt", secret.name(), account.0);
+                None
+            },
+        }
+    }
+
+    fn save(&self, account: &AccountID, secret: Secret, value: &str) -> Result<(), AccountError> {
+        let mut nonce = [0; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = self.cipher
+            .encrypt(Nonce::from_slice(&nonce), value.as_bytes())
+            .or(Err(AccountError::SessionStoreError))?;
+
+        // Written beside the real file and renamed over it, so a crash mid-write can't leave a half-written secret.
+        let path = self.path_for(account, secret);
+        let temp_path = self.dir.join(format!("{}.{}.tmp", account.0, secret.name()));
+        fs::write(&temp_path, [&nonce[..], &ciphertext].concat()).or(Err(AccountError::SessionStoreError))?;
+        restrict_permissions(&temp_path);
+        fs::rename(&temp_path, &path).map_err(|e| {
+            eprintln!("Failed to replace saved {}: {}", secret.name(), e);
+            let _ = fs::remove_file(&temp_path);
+            AccountError::SessionStoreError
+        })
+    }
+
+    fn delete(&self, account: &AccountID, secret: Secret) -> Result<(), AccountError> {
+        match fs::remove_file(self.path_for(account, secret)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                eprintln!("Failed to delete saved {}: {}", secret.name(), e);
+                Err(AccountError::SessionStoreError)
+            },
+        }
+    }
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+        eprintln!("Failed to restrict permissions on {:?}: {}", path, e);
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+
+/// Forgets everything when the app closes.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    values: Mutex<HashMap<(AccountID, Secret), String>>,
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self, account: &AccountID, secret: Secret) -> Option<String> {
+        self.values.lock().ok()?.get(&(account.clone(), secret)).cloned()
+    }
+
+    fn save(&self, account: &AccountID, secret: Secret, value: &str) -> Result<(), AccountError> {
+        self.values
+            .lock()
+            .or(Err(AccountError::LockError))?
+            .insert((account.clone(), secret), value.to_owned());
+        Ok(())
+    }
+
+    fn delete(&self, account: &AccountID, secret: Secret) -> Result<(), AccountError> {
+        self.values
+            .lock()
+            .or(Err(AccountError::LockError))?
+            .remove(&(account.clone(), secret));
+        Ok(())
+    }
+}
+
+
+/// The cookie provider handed to reqwest. Works like a bare `CookieStoreMutex`, except the jar is written
+/// back to the session store whenever a response changes them, so a refreshed session survives a restart.
+#[derive(Debug)]
+pub struct PersistentCookies {
+    jar: Arc<CookieStoreMutex>,
+    account: AccountID,
+    store: Arc<dyn SessionStore>,
+    /// What the store last got, so responses that set the same cookies again don't rewrite it.
+    last_saved: Mutex<Option<Vec<Vec<u8>>>>,
+}
+
+impl PersistentCookies {
+    /// Starts from an empty jar when nothing was saved or the saved cookies can't be read.
+    pub fn load(account: AccountID, store: Arc<dyn SessionStore>) -> Self {
+        let raw_cookie_store = restore_jar(&account, store.as_ref()).unwrap_or_default();
+        let last_saved = fingerprint(&raw_cookie_store).ok();
+
+        Self {
+            jar: Arc::new(CookieStoreMutex::new(raw_cookie_store)),
+            account,
+            store,
+            last_saved: Mutex::new(last_saved),
+        }
+    }
+
+    /// Empties the jar and deletes the saved copy. Both run even if one fails, and the first failure is returned.
+    pub fn clear(&self) -> Result<(), AccountError> {
+        let jar = self.jar
+            .lock()
+            .map(|mut jar| *jar = CookieStore::default())
+            .or(Err(AccountError::LockError));
+        let saved = self.store.delete(&self.account, Secret::Cookies);
+        // the saved copy is gone, so the next login has to be written even if it sets the same cookies as the last one
+        let last_saved = self.last_saved
+            .lock()
+            .map(|mut last_saved| *last_saved = None)
+            .or(Err(AccountError::LockError));
+
+        jar.and(saved).and(last_saved)
+    }
+
+    pub fn jar(&self) -> Arc<CookieStoreMutex> {
+        self.jar.clone()
+    }
+
+    fn save(&self) {
+        let serialized = match self.jar.lock() {
+            Ok(inner_jar) => fingerprint(&inner_jar).and_then(|fingerprint| Ok((
+                fingerprint,
+                bincode::serialize(&inner_jar.iter_unexpired().collect::<Vec<_>>())?,
+            ))),
+            Err(e) => return eprintln!("Failed to get lock on cookie jar: {}", e),
+        };
+
+        let (fingerprint, serialized_value) = match serialized {
+            Ok(serialized) => serialized,
+            Err(e) => return eprintln!("Failed to serialize cookies into binary: {}", e),
+        };
+
+        let mut last_saved = match self.last_saved.lock() {
+            Ok(last_saved) => last_saved,
+            Err(e) => return eprintln!("Failed to get lock on saved cookies: {}", e),
+        };
+        if last_saved.as_ref() == Some(&fingerprint) {
+            return;
+        }
+
+        match self.store.save(&self.account, Secret::Cookies, &base64::encode(serialized_value)) {
+            Ok(()) => *last_saved = Some(fingerprint),
+            Err(e) => eprintln!("Failed to save cookies: {}", e),
+        }
+    }
+}
+
+/// The jar as it was last saved. Cookies that can't be put back are skipped on their own.
+fn restore_jar(account: &AccountID, store: &dyn SessionStore) -> Option<CookieStore> {
+    let encoded = store.load(account, Secret::Cookies)?;
+    let bytes = base64::decode(encoded)
+        .map_err(|e| eprintln!("Saved cookies for {} aren't valid base64: {}", account.0, e))
+        .ok()?;
+    let saved = bincode::deserialize::<Vec<Cookie>>(&bytes)
+        .map_err(|e| eprintln!("Failed to deserialize saved cookies for {}: {}", account.0, e))
+        .ok()?;
+
+    let mut jar = CookieStore::default();
+    for cookie in saved {
+        let url = match cookie.domain.as_cow().and_then(|domain| Url::parse(&format!("https://{}", domain)).ok()) {
+            Some(url) => url,
+            None => {
+                eprintln!("Skipping saved cookie {} without a usable domain", cookie.name());
+                continue;
+            },
+        };
+        let name = cookie.name().to_owned();
+        if let Err(e) = jar.insert(cookie, &url) {
+            eprintln!("Failed to restore cookie {}: {}", name, e);
+        }
+    }
+
+    Some(jar)
+}
+
+/// The jar's cookies in an order that doesn't depend on how it happens to store them, for telling whether
+/// anything changed since the last save.
+fn fingerprint(jar: &CookieStore) -> bincode::Result<Vec<Vec<u8>>> {
+    let mut cookies = jar
+        .iter_unexpired()
+        .map(bincode::serialize)
+        .collect::<bincode::Result<Vec<_>>>()?;
+    cookies.sort();
+    Ok(cookies)
+}
+
+impl ReqwestCookieStore for PersistentCookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        self.jar.set_cookies(cookie_headers, url);
+        self.save();
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.cookies(url)
+    }
+}
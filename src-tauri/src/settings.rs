@@ -0,0 +1,95 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::Mutex};
+
+use bbs_shared::{accounts::AccountID, settings::{AccountSettings, SessionStoreKind, Settings}, errors::SettingsError};
+use serde::{Serialize, Deserialize};
+
+/// What's in `settings.json`. Only the session store is shared; everything else belongs to one account.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsFile {
+    session_store: SessionStoreKind,
+    accounts: HashMap<AccountID, AccountSettings>,
+    /// Settings saved before they were kept per account. Accounts without their own start from these.
+    unclaimed: AccountSettings,
+}
+
+impl SettingsFile {
+    fn parse(json: &str) -> serde_json::Result<Self> {
+        let is_legacy = serde_json::from_str::<serde_json::Value>(json)?.get("accounts").is_none();
+
+        if is_legacy {
+            let (session_store, unclaimed) = serde_json::from_str::<Settings>(json)?.split();
+            Ok(Self {
+                session_store,
+                accounts: HashMap::new(),
+                unclaimed,
+            })
+        } else {
+            serde_json::from_str(json)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: Mutex<SettingsFile>,
+}
+
+impl SettingsStore {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let settings = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| match SettingsFile::parse(&json) {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    eprintln!("Failed to parse settings, using defaults: {}", e);
+                    None
+                },
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn session_store(&self) -> SessionStoreKind {
+        self.settings
+            .lock()
+            .map(|settings| settings.session_store)
+            .unwrap_or_default()
+    }
+
+    /// The shared settings along with `account`'s own.
+    pub fn get(&self, account: &AccountID) -> Settings {
+        self.settings
+            .lock()
+            .map(|settings| Settings::join(
+                settings.session_store,
+                settings.accounts.get(account).unwrap_or(&settings.unclaimed).clone(),
+            ))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, account: &AccountID, settings: Settings) -> Result<(), SettingsError> {
+        let (session_store, account_settings) = settings.split();
+
+        let mut file = self.settings.lock().or(Err(SettingsError::LockError))?;
+        file.session_store = session_store;
+        file.accounts.insert(account.clone(), account_settings);
+
+        let json = serde_json::to_string_pretty(&*file).or(Err(SettingsError::WriteError))?;
+
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).or(Err(SettingsError::WriteError))?;
+            }
+            fs::write(path, json).or(Err(SettingsError::WriteError))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Single sign-on for schools that send students to Google, Microsoft or another SAML provider instead of
+//! Schoology's own login form.
+//!
+//! The provider's pages run in a separate webview window, since they need a real browser. They finish by
+//! auto-submitting a form with a `SAMLResponse` to Schoology. `CAPTURE_SCRIPT` points that form at the
+//! `bbs-sso` scheme instead, so it arrives at `handle_sso_request` on the host side. The host then posts
+//! it with the account's own client. Schoology's session cookies land in that client's jar, and the
+//! webview never holds the session. Remote pages get no IPC: every command rejects windows other than
+//! `MAIN_WINDOW`.
+
+use std::{collections::HashMap, error::Error};
+
+use bbs_shared::{errors::LoginError, events::Event};
+use reqwest::Url;
+use tauri::{http::{Request as HttpRequest, Response as HttpResponse, ResponseBuilder}, AppHandle, Manager, Runtime, Window, WindowBuilder, WindowUrl};
+
+use crate::{accounts::Accounts, requests::{get_login_page, submit_saml_response, LoginPage, SCHOOLOGY_BASE}};
+
+pub const SSO_WINDOW: &str = "sso";
+pub const SSO_SCHEME: &str = "bbs-sso";
+
+// same mapping as the asset scheme, see `assets::ASSET_BASE`
+#[cfg(windows)]
+const CAPTURE_URL: &str = "https://bbs-sso.localhost/capture";
+
+#[cfg(not(windows))]
+const CAPTURE_URL: &str = "bbs-sso://localhost/capture";
+
+/// Holds the form's real destination while it travels to `CAPTURE_URL`.
+const TARGET_FIELD: &str = "bbs_sso_target";
+
+/// Runs at the start of every page in the SSO window. Providers call `form.submit()` directly as well as
+/// submitting normally, so both are covered. The form is turned into a GET to the capture url, which puts
+/// every field in the query string.
+const CAPTURE_SCRIPT: &str = r#"
+(function () {
+    function isSaml(form) {
+        return form instanceof HTMLFormElement && form.querySelector("input[name=SAMLResponse]") !== null;
+    }
+
+    function redirect(form) {
+        var target = document.createElement("input");
+        target.type = "hidden";
+        target.name = "TARGET_FIELD";
+        target.value = form.action;
+        form.appendChild(target);
+        form.method = "get";
+        form.action = "CAPTURE_URL";
+    }
+
+    var submit = HTMLFormElement.prototype.submit;
+    HTMLFormElement.prototype.submit = function () {
+        if (isSaml(this)) {
+            redirect(this);
+        }
+        submit.call(this);
+    };
+
+    document.addEventListener("submit", function (event) {
+        if (isSaml(event.target)) {
+            redirect(event.target);
+        }
+    }, true);
+})();
+"#;
+
+/// Opens Schoology's login page in its own window, or focuses the one already open.
+pub fn open_sso_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Window<R>> {
+    if let Some(window) = app.get_window(SSO_WINDOW) {
+        window.set_focus()?;
+        return Ok(window);
+    }
+
+    let url = Url::parse(&format!("{}/login", SCHOOLOGY_BASE)).expect("SCHOOLOGY_BASE is a valid url");
+    let script = CAPTURE_SCRIPT
+        .replace("TARGET_FIELD", TARGET_FIELD)
+        .replace("CAPTURE_URL", CAPTURE_URL);
+
+    WindowBuilder::new(app, SSO_WINDOW, WindowUrl::External(url))
+        .title("Sign in to Schoology")
+        .inner_size(480.0, 640.0)
+        .initialization_script(&script)
+        .build()
+}
+
+/// The SAML response is as good as a password, so it only ever goes back to Schoology.
+pub fn is_schoology_url(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "https")
+        .and_then(|url| url.host_str().map(|host| host == "schoology.com" || host.ends_with(".schoology.com")))
+        .unwrap_or(false)
+}
+
+/// Receives the redirected SAML form. The actual sign in happens in the background so the webview isn't
+/// blocked on Schoology; the window just shows a placeholder until `finish_sso` closes it.
+pub fn handle_sso_request<R: Runtime>(app: &AppHandle<R>, request: &HttpRequest) -> Result<HttpResponse, Box<dyn Error>> {
+    let mut fields: HashMap<String, String> = Url::parse(request.uri())?
+        .query_pairs()
+        .into_owned()
+        .collect();
+
+    let target = fields.remove(TARGET_FIELD).filter(|target| is_schoology_url(target));
+
+    match target {
+        Some(target) if app.get_window(SSO_WINDOW).is_some() => {
+            tauri::async_runtime::spawn(finish_sso(app.clone(), target, fields));
+
+            ResponseBuilder::new()
+                .status(200)
+                .mimetype("text/html")
+                .body(b"<p style=\"font-family: sans-serif\">Finishing sign in\xE2\x80\xA6</p>".to_vec())
+        },
+        _ => ResponseBuilder::new()
+            .status(400)
+            .body(Vec::new()),
+    }
+}
+
+async fn finish_sso<R: Runtime>(app: AppHandle<R>, action: String, fields: HashMap<String, String>) {
+    let account = app.state::<Accounts>().active();
+    let client = &account.client.client;
+
+    let result = match submit_saml_response(client, &action, &fields).await {
+        // the home page only shows a login form when the session didn't take
+        Ok(()) => match get_login_page(client).await {
+            Ok(LoginPage::SignedIn) => Ok(()),
+            Ok(LoginPage::Form(_)) => Err(LoginError::SsoFailed("Schoology still shows its login form".to_owned())),
+            Err(LoginError::SsoRequired) => Err(LoginError::SsoFailed("Schoology sent the sign in back to the identity provider".to_owned())),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+
+    match Event::SsoFinished(result.err()).to_base64() {
+        Ok(payload) => if let Err(e) = app.emit_all("bbs-event", payload) {
+            eprintln!("Failed to emit SSO result: {}", e);
+        },
+        Err(e) => eprintln!("Failed to serialize SSO result: {}", e),
+    }
+
+    if let Some(window) = app.get_window(SSO_WINDOW) {
+        if let Err(e) = window.close() {
+            eprintln!("Failed to close SSO window: {}", e);
+        }
+    }
+}
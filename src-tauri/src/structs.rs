@@ -78,6 +78,16 @@ pub struct ClassesById {
     pub data: HashMap<u64, (ActiveClassesCourse, ActiveClassesSection)>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeFeedPage {
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingPage {
+    pub html: String,
+}
+
 #[derive(Debug)]
 pub struct AugClient {
     pub client: Client,